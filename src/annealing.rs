@@ -1,18 +1,32 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use crate::common::*;
+use crate::encoding::Mutate;
+use crate::engine::Evolve;
+use crate::fitness::*;
+use crate::gears::Valuer;
+use crate::individual::*;
+use crate::population::*;
+use crate::random::*;
+use crate::report::Report;
+use crate::units::{EnergyUnit, Temperature};
+// imports:1 ends here
+
 // [[file:../spdkit.note::*base][base:1]]
 /// Simulated annealing.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Annealer {
-    pub(crate) temperature_high: f64,
-    pub(crate) temperature_low: f64,
+    pub(crate) temperature_high: Temperature,
+    pub(crate) temperature_low: Temperature,
     cooling_rate: f64,
+    #[serde(skip)]
     temperature: Option<f64>,
 }
 
 impl Default for Annealer {
     fn default() -> Self {
         Self {
-            temperature_high: 5000.0,
-            temperature_low: 2000.0,
+            temperature_high: Temperature::kelvin(5000.0),
+            temperature_low: Temperature::kelvin(2000.0),
             cooling_rate: 0.92,
             temperature: None,
         }
@@ -21,7 +35,9 @@ impl Default for Annealer {
 
 impl Annealer {
     /// Construct Annealer with temperature high `th` and temperate low `tl`.
-    pub fn new(th: f64, tl: f64) -> Self {
+    pub fn new(th: impl Into<Temperature>, tl: impl Into<Temperature>) -> Self {
+        let th = th.into();
+        let tl = tl.into();
         assert!(th > tl, "temperature_low is high than temperature_high!");
 
         Self {
@@ -41,24 +57,251 @@ impl Annealer {
 
     /// Reset tempeature to initial state.
     pub fn reset(&mut self) {
-        self.temperature = Some(self.temperature_high);
+        self.temperature = Some(self.temperature_high.to_kelvin());
     }
 
     /// Return an iterator over temperature.
-    pub fn start(&mut self) -> impl Iterator<Item = f64> + '_ {
+    pub fn start(&mut self) -> impl Iterator<Item = Temperature> + '_ {
         std::iter::from_fn(move || {
-            let temp: &mut f64 = self.temperature.get_or_insert(self.temperature_high);
+            let temp: &mut f64 = self.temperature.get_or_insert(self.temperature_high.to_kelvin());
             *temp *= self.cooling_rate;
 
-            if *temp <= self.temperature_low {
+            if *temp <= self.temperature_low.to_kelvin() {
                 None
             } else {
-                Some(*temp)
+                Some(Temperature::kelvin(*temp))
             }
         })
     }
 }
 
+// base:1 ends here
+
+// [[file:../spdkit.note::3e9a7d21][3e9a7d21]]
+/// Cooling schedule for simulated annealing.
+pub trait CoolingSchedule {
+    /// Return the temperature at Monte Carlo step `k`, counting from 0.
+    fn temperature(&self, k: usize) -> Temperature;
+}
+
+/// Geometric cooling with temperature floor: T_k = max(T_high r^(k+1), T_low).
+impl CoolingSchedule for Annealer {
+    fn temperature(&self, k: usize) -> Temperature {
+        let th = self.temperature_high.to_kelvin();
+        let tl = self.temperature_low.to_kelvin();
+        let t = th * self.cooling_rate.powi(k as i32 + 1);
+        Temperature::kelvin(t.max(tl))
+    }
+}
+
+/// Constant temperature, that is, Metropolis Monte Carlo sampling.
+impl CoolingSchedule for Temperature {
+    fn temperature(&self, _k: usize) -> Temperature {
+        *self
+    }
+}
+
+/// Linear cooling from `T_high` to `T_low` in `n` steps.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LinearCooling {
+    temperature_high: Temperature,
+    temperature_low: Temperature,
+    nsteps: usize,
+}
+
+impl LinearCooling {
+    /// Construct with temperature high `th`, temperature low `tl`, and the
+    /// number of cooling steps `n`.
+    pub fn new(th: impl Into<Temperature>, tl: impl Into<Temperature>, n: usize) -> Self {
+        let th = th.into();
+        let tl = tl.into();
+        assert!(th > tl, "temperature_low is high than temperature_high!");
+        assert!(n > 0, "invalid number of steps: {n}");
+        Self {
+            temperature_high: th,
+            temperature_low: tl,
+            nsteps: n,
+        }
+    }
+}
+
+impl CoolingSchedule for LinearCooling {
+    fn temperature(&self, k: usize) -> Temperature {
+        let th = self.temperature_high.to_kelvin();
+        let tl = self.temperature_low.to_kelvin();
+        let x = (k as f64 / self.nsteps as f64).min(1.0);
+        Temperature::kelvin(th - (th - tl) * x)
+    }
+}
+
+/// Logarithmic cooling: T_k = T_0 / ln(k + e).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LogarithmicCooling {
+    temperature: Temperature,
+}
+
+impl LogarithmicCooling {
+    /// Construct with initial temperature `t0`.
+    pub fn new(t0: impl Into<Temperature>) -> Self {
+        Self { temperature: t0.into() }
+    }
+}
+
+impl CoolingSchedule for LogarithmicCooling {
+    fn temperature(&self, k: usize) -> Temperature {
+        let t0 = self.temperature.to_kelvin();
+        Temperature::kelvin(t0 / (k as f64 + std::f64::consts::E).ln())
+    }
+}
+// 3e9a7d21 ends here
+
+// [[file:../spdkit.note::5a41c8e6][5a41c8e6]]
+/// Metropolis criterion: accept a move changing energy by `delta` (in
+/// `unit`) with the probability of min(1, exp(-delta/kT)).
+pub fn metropolis<R: Rng + Sized>(delta: f64, temperature: Temperature, unit: EnergyUnit, rng: &mut R) -> bool {
+    delta <= 0.0 || rng.gen::<f64>() < temperature.boltzmann_factor(delta, unit)
+}
+
+/// Single-trajectory simulated annealing over genomes with `Mutate`
+/// implementation.
+///
+/// The objective value of individual is treated as energy to be minimized,
+/// unless `maximize` is set. In each generation, a number of Monte Carlo
+/// steps are performed: a trial genome is created by mutating current genome,
+/// and is accepted using Metropolis criterion at the temperature from cooling
+/// schedule. The population in each generation consists of the current
+/// individual only.
+#[derive(Clone, Debug)]
+pub struct SimulatedAnnealing<S: CoolingSchedule> {
+    schedule: S,
+    unit: EnergyUnit,
+    maximize: bool,
+    nsteps: usize,
+    mutation_size: usize,
+
+    // the number of Monte Carlo steps performed in total
+    step: usize,
+    // the number of accepted moves in last generation and in total
+    naccepted: usize,
+    naccepted_total: usize,
+    temperature: Option<Temperature>,
+}
+
+impl<S: CoolingSchedule> SimulatedAnnealing<S> {
+    /// Construct with cooling `schedule`.
+    pub fn new(schedule: S) -> Self {
+        Self {
+            schedule,
+            unit: EnergyUnit::default(),
+            maximize: false,
+            nsteps: 10,
+            mutation_size: 1,
+            step: 0,
+            naccepted: 0,
+            naccepted_total: 0,
+            temperature: None,
+        }
+    }
+
+    /// Set energy unit of individual objective value.
+    pub fn energy_unit(mut self, u: EnergyUnit) -> Self {
+        self.unit = u;
+        self
+    }
+
+    /// Maximize objective value instead of minimizing it.
+    pub fn maximize(mut self) -> Self {
+        self.maximize = true;
+        self
+    }
+
+    /// Set the number of Monte Carlo steps per generation. The default is 10.
+    pub fn steps_per_generation(mut self, n: usize) -> Self {
+        assert!(n > 0, "invalid number of steps: {n}");
+        self.nsteps = n;
+        self
+    }
+
+    /// Set the number of bits/points to mutate in each move. The default is 1.
+    pub fn mutation_size(mut self, n: usize) -> Self {
+        assert!(n > 0, "invalid mutation size: {n}");
+        self.mutation_size = n;
+        self
+    }
+
+    /// Return the acceptance rate in last generation.
+    pub fn acceptance_rate(&self) -> f64 {
+        self.naccepted as f64 / self.nsteps as f64
+    }
+
+    /// Return the acceptance rate since start.
+    pub fn acceptance_rate_total(&self) -> f64 {
+        if self.step > 0 {
+            self.naccepted_total as f64 / self.step as f64
+        } else {
+            0.0
+        }
+    }
+
+    fn energy<G: Genome>(&self, indv: &Individual<G>) -> f64 {
+        if self.maximize {
+            -indv.objective_value()
+        } else {
+            indv.objective_value()
+        }
+    }
+}
+
+impl<G, F, C, S> Evolve<G, F, C> for SimulatedAnnealing<S>
+where
+    G: Genome + Mutate,
+    F: EvaluateFitness<G>,
+    C: EvaluateObjectiveValue<G>,
+    S: CoolingSchedule,
+{
+    fn next_generation(&mut self, cur_population: &Population<G>, valuer: &mut Valuer<G, F, C>) -> Population<G> {
+        let mut rng = get_rng!();
+        let mut current = cur_population
+            .best_member()
+            .expect("empty population")
+            .individual
+            .to_owned();
+
+        self.naccepted = 0;
+        for _ in 0..self.nsteps {
+            let temperature = self.schedule.temperature(self.step);
+            self.temperature = Some(temperature);
+            self.step += 1;
+
+            let mut genome = current.genome().clone();
+            genome.mutate(self.mutation_size, &mut *rng);
+            let trial = valuer.create_individuals(vec![genome]).pop().expect("no individual");
+
+            // Metropolis criterion
+            let delta = self.energy(&trial) - self.energy(&current);
+            if metropolis(delta, temperature, self.unit, &mut *rng) {
+                current = trial;
+                self.naccepted += 1;
+            }
+        }
+        self.naccepted_total += self.naccepted;
+
+        valuer.build_population(vec![current]).with_size_limit(1)
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        report.set("sa/acceptance_rate", self.acceptance_rate());
+        report.set("sa/acceptance_rate_total", self.acceptance_rate_total());
+        if let Some(t) = self.temperature {
+            report.set("sa/temperature", t.to_kelvin());
+        }
+        report
+    }
+}
+// 5a41c8e6 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
 #[test]
 fn test_annealer() {
     let mut ann = Annealer::new(500.0, 100.0);
@@ -67,4 +310,42 @@ fn test_annealer() {
         dbg!(x);
     }
 }
-// base:1 ends here
+
+#[test]
+fn test_cooling_schedule() {
+    let ann = Annealer::new(500.0, 100.0).cooling_rate(0.5);
+    assert_eq!(ann.temperature(0).to_kelvin(), 250.0);
+    assert_eq!(ann.temperature(10).to_kelvin(), 100.0);
+
+    let linear = LinearCooling::new(500.0, 100.0, 4);
+    assert_eq!(linear.temperature(2).to_kelvin(), 300.0);
+    assert_eq!(linear.temperature(8).to_kelvin(), 100.0);
+
+    let log = LogarithmicCooling::new(500.0);
+    assert_eq!(log.temperature(0).to_kelvin(), 500.0);
+}
+
+#[test]
+fn test_simulated_annealing() -> Result<()> {
+    use crate::encoding::Binary;
+    use crate::engine::Engine;
+
+    let valuer = Valuer::new().with_fitness(Maximize).with_creator(OneMax);
+    let t0 = Temperature::from_kt(1.0, EnergyUnit::ElectronVolt);
+    let t1 = Temperature::from_kt(0.01, EnergyUnit::ElectronVolt);
+    let algo = SimulatedAnnealing::new(LinearCooling::new(t0, t1, 200)).maximize();
+    let seeds = vec![Binary::from_str("00000000000000000000")];
+    let mut engine = Engine::create().valuer(valuer).algorithm(algo).termination_nlast(100);
+    let mut best = 0.0;
+    for g in engine.evolve(&seeds).skip(1).take(30) {
+        let generation = g?;
+        assert_eq!(generation.population.size(), 1);
+        let rate = generation.report.get("sa/acceptance_rate").and_then(|x| x.as_number()).unwrap();
+        assert!((0.0..=1.0).contains(&rate));
+        best = generation.population.best_member().unwrap().objective_value();
+    }
+    assert!(best > 10.0, "{best}");
+
+    Ok(())
+}
+// test:1 ends here
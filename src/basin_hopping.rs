@@ -0,0 +1,263 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use gchemol::Molecule;
+
+use crate::annealing::metropolis;
+use crate::common::*;
+use crate::fingerprint::FingerPrintExt;
+use crate::random::*;
+use crate::units::{EnergyUnit, Temperature};
+// imports:1 ends here
+
+// [[file:../spdkit.note::9b1e4f63][9b1e4f63]]
+/// Local relaxation of molecular structure, e.g. geometry optimization using
+/// an external program.
+pub trait Relax {
+    /// Relax `mol` in place to a nearby local minimum, and return its energy.
+    fn relax(&mut self, mol: &mut Molecule) -> Result<f64>;
+}
+
+/// A unique local minimum found in basin hopping.
+#[derive(Debug, Clone)]
+pub struct Minimum {
+    /// The relaxed structure with the lowest energy among visits.
+    pub molecule: Molecule,
+    pub energy: f64,
+    pub fingerprint: String,
+    /// The number of times the minimum has been visited.
+    pub nvisits: usize,
+}
+
+/// Represents a basin-hopping step.
+#[derive(Debug, Clone)]
+pub struct HoppingStep {
+    pub index: usize,
+    /// The energy of relaxed trial structure.
+    pub energy: f64,
+    /// Whether the trial structure is accepted as current structure.
+    pub accepted: bool,
+    /// Whether the trial structure is a minimum never seen before.
+    pub new_minimum: bool,
+    /// The energy of current structure after this step.
+    pub current_energy: f64,
+    /// The lowest energy found so far.
+    pub best_energy: f64,
+}
+
+/// Basin hopping global optimization: perturb current structure, relax it to
+/// local minimum, and accept or reject it by Metropolis criterion.
+///
+/// Unique minima are identified by `FingerPrintExt::fingerprint` and kept in
+/// an archive.
+///
+/// # Reference
+///
+/// * Wales and Doye 1997, J. Phys. Chem. A, 101, 5111
+///
+pub struct BasinHopping<R: Relax> {
+    relaxer: R,
+    temperature: Temperature,
+    unit: EnergyUnit,
+    step_size: f64,
+
+    archive: Vec<Minimum>,
+    // current structure and its energy
+    current: Option<(Molecule, f64)>,
+    nsteps: usize,
+    naccepted: usize,
+}
+
+impl<R: Relax> BasinHopping<R> {
+    /// Construct with local `relaxer` and Metropolis `temperature`.
+    pub fn new(relaxer: R, temperature: impl Into<Temperature>) -> Self {
+        Self {
+            relaxer,
+            temperature: temperature.into(),
+            unit: EnergyUnit::default(),
+            step_size: 0.5,
+            archive: vec![],
+            current: None,
+            nsteps: 0,
+            naccepted: 0,
+        }
+    }
+
+    /// Set energy unit returned by `Relax::relax`.
+    pub fn energy_unit(mut self, u: EnergyUnit) -> Self {
+        self.unit = u;
+        self
+    }
+
+    /// Set the max displacement of atoms in each Cartesian direction in
+    /// perturbation. The default is 0.5 Å.
+    pub fn step_size(mut self, s: f64) -> Self {
+        assert!(s > 0.0, "invalid step size: {s}");
+        self.step_size = s;
+        self
+    }
+
+    /// Return unique minima found so far, from the lowest energy to the
+    /// highest.
+    pub fn archive(&self) -> &[Minimum] {
+        &self.archive
+    }
+
+    /// Return the lowest minimum found so far.
+    pub fn best(&self) -> Option<&Minimum> {
+        self.archive.first()
+    }
+
+    /// Return the fraction of accepted trial structures.
+    pub fn acceptance_rate(&self) -> f64 {
+        if self.nsteps > 0 {
+            self.naccepted as f64 / self.nsteps as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+impl<R: Relax> BasinHopping<R> {
+    // Randomly displace all atoms in `mol`.
+    fn perturb(&self, mol: &mut Molecule) {
+        let mut rng = get_rng!();
+        let s = self.step_size;
+        let positions: Vec<_> = mol
+            .positions()
+            .map(|p| [0, 1, 2].map(|k| p[k] + rng.gen_range(-s..s)))
+            .collect();
+        mol.set_positions(positions);
+    }
+
+    // Add a relaxed structure into archive. Return true if it is a new
+    // minimum.
+    fn archive_minimum(&mut self, mol: &Molecule, energy: f64) -> bool {
+        let mut mol = mol.clone();
+        mol.rebond();
+        let fingerprint = mol.fingerprint();
+        let is_new = if let Some(m) = self.archive.iter_mut().find(|m| m.fingerprint == fingerprint) {
+            m.nvisits += 1;
+            if energy < m.energy {
+                m.energy = energy;
+                m.molecule = mol;
+            }
+            false
+        } else {
+            self.archive.push(Minimum {
+                molecule: mol,
+                energy,
+                fingerprint,
+                nvisits: 1,
+            });
+            true
+        };
+        self.archive.sort_by(|a, b| float_ordering_minimize(&a.energy, &b.energy));
+        is_new
+    }
+
+    fn hop(&mut self, index: usize, mol: &Molecule) -> Result<HoppingStep> {
+        let (energy, accepted, new_minimum) = match self.current.take() {
+            // the first step: relax the initial structure
+            None => {
+                let mut trial = mol.clone();
+                let energy = self.relaxer.relax(&mut trial)?;
+                let new_minimum = self.archive_minimum(&trial, energy);
+                self.current = Some((trial, energy));
+                (energy, true, new_minimum)
+            }
+            Some((cur, ecur)) => {
+                let mut trial = cur.clone();
+                self.perturb(&mut trial);
+                let energy = match self.relaxer.relax(&mut trial) {
+                    Ok(e) => e,
+                    Err(e) => {
+                        self.current = Some((cur, ecur));
+                        return Err(e);
+                    }
+                };
+                let new_minimum = self.archive_minimum(&trial, energy);
+                self.nsteps += 1;
+
+                // Metropolis criterion
+                let delta = energy - ecur;
+                let accepted = metropolis(delta, self.temperature, self.unit, &mut *get_rng!());
+                if accepted {
+                    self.naccepted += 1;
+                    self.current = Some((trial, energy));
+                } else {
+                    self.current = Some((cur, ecur));
+                }
+                (energy, accepted, new_minimum)
+            }
+        };
+
+        let current_energy = self.current.as_ref().map(|x| x.1).unwrap();
+        let best_energy = self.best().map(|m| m.energy).unwrap();
+        Ok(HoppingStep {
+            index,
+            energy,
+            accepted,
+            new_minimum,
+            current_energy,
+            best_energy,
+        })
+    }
+
+    /// Run basin hopping from initial structure `mol`.
+    ///
+    /// # Returns
+    ///
+    /// * return an iterator over `HoppingStep`. The first step is the
+    ///   relaxation of `mol`.
+    pub fn run<'a>(&'a mut self, mol: &'a Molecule) -> impl Iterator<Item = Result<HoppingStep>> + 'a {
+        let mut index = 0;
+        std::iter::from_fn(move || {
+            let step = self.hop(index, mol);
+            index += 1;
+            if let Ok(step) = &step {
+                info!(
+                    "hopping step {}: energy = {:-12.5}, accepted = {}, best = {:-12.5}",
+                    step.index, step.energy, step.accepted, step.best_energy
+                );
+            }
+            Some(step)
+        })
+    }
+}
+// 9b1e4f63 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use gchemol::prelude::*;
+
+    // snap atoms onto integer grid, with energy as the sum of squared
+    // distances to origin
+    struct SnapToGrid;
+
+    impl Relax for SnapToGrid {
+        fn relax(&mut self, mol: &mut Molecule) -> Result<f64> {
+            let positions: Vec<_> = mol.positions().map(|p| p.map(|x| x.round())).collect();
+            mol.set_positions(positions.clone());
+            Ok(positions.iter().flatten().map(|x| x * x).sum())
+        }
+    }
+
+    #[test]
+    fn test_basin_hopping() -> Result<()> {
+        let mol = Molecule::from_file("./tests/files/CH4.xyz")?;
+        let mut bh = BasinHopping::new(SnapToGrid, 300.0).step_size(1.0);
+        let steps: Vec<_> = bh.run(&mol).take(20).collect::<Result<_>>()?;
+        assert!(steps[0].accepted);
+        assert!(steps.last().unwrap().best_energy <= steps[0].energy);
+
+        let archive = bh.archive();
+        assert!(!archive.is_empty());
+        assert_eq!(archive.iter().map(|m| m.nvisits).sum::<usize>(), 20);
+        assert!(archive.windows(2).all(|w| w[0].energy <= w[1].energy));
+        assert!((0.0..=1.0).contains(&bh.acceptance_rate()));
+
+        Ok(())
+    }
+}
+// test:1 ends here
@@ -0,0 +1,472 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use std::collections::{HashMap, VecDeque};
+
+use vecfx::nalgebra::{DMatrix, DVector};
+
+use crate::common::*;
+use crate::encoding::{Bounds, RealVector};
+use crate::engine::Evolve;
+use crate::fitness::*;
+use crate::gears::Valuer;
+use crate::individual::*;
+use crate::population::*;
+use crate::random::*;
+use crate::report::Report;
+// imports:1 ends here
+
+// [[file:../spdkit.note::8c3f52d0][8c3f52d0]]
+/// Restart strategies of CMA-ES.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CmaRestart {
+    /// No restart.
+    #[default]
+    None,
+    /// Restart with doubled population size (IPOP-CMA-ES).
+    Ipop,
+    /// Restart alternately in large population regime with doubled population
+    /// size, and in small population regime with random population size and
+    /// step size (BIPOP-CMA-ES).
+    Bipop,
+}
+
+// The internal state of a single CMA-ES run.
+#[derive(Debug, Clone)]
+struct CmaState {
+    lambda: usize,
+    weights: Vec<f64>,
+    mueff: f64,
+    cc: f64,
+    cs: f64,
+    c1: f64,
+    cmu: f64,
+    damps: f64,
+    chin: f64,
+
+    mean: DVector<f64>,
+    sigma: f64,
+    cov: DMatrix<f64>,
+    pc: DVector<f64>,
+    ps: DVector<f64>,
+    // eigen decomposition of covariance matrix: C = B D^2 B^T
+    b: DMatrix<f64>,
+    d: DVector<f64>,
+
+    generation: usize,
+    // the best objective values in recent generations
+    history: VecDeque<f64>,
+}
+
+impl CmaState {
+    fn new(mean: DVector<f64>, sigma: f64, lambda: usize) -> Self {
+        let n = mean.len();
+        let nf = n as f64;
+        let mu = lambda / 2;
+        let weights: Vec<_> = (1..=mu)
+            .map(|i| (mu as f64 + 0.5).ln() - (i as f64).ln())
+            .collect();
+        let wsum: f64 = weights.iter().sum();
+        let weights: Vec<_> = weights.into_iter().map(|w| w / wsum).collect();
+        let mueff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+
+        let cc = (4.0 + mueff / nf) / (nf + 4.0 + 2.0 * mueff / nf);
+        let cs = (mueff + 2.0) / (nf + mueff + 5.0);
+        let c1 = 2.0 / ((nf + 1.3).powi(2) + mueff);
+        let cmu = (1.0 - c1).min(2.0 * (mueff - 2.0 + 1.0 / mueff) / ((nf + 2.0).powi(2) + mueff));
+        let damps = 1.0 + 2.0 * (((mueff - 1.0) / (nf + 1.0)).sqrt() - 1.0).max(0.0) + cs;
+        let chin = nf.sqrt() * (1.0 - 1.0 / (4.0 * nf) + 1.0 / (21.0 * nf * nf));
+
+        Self {
+            lambda,
+            weights,
+            mueff,
+            cc,
+            cs,
+            c1,
+            cmu,
+            damps,
+            chin,
+            mean,
+            sigma,
+            cov: DMatrix::identity(n, n),
+            pc: DVector::zeros(n),
+            ps: DVector::zeros(n),
+            b: DMatrix::identity(n, n),
+            d: DVector::from_element(n, 1.0),
+            generation: 0,
+            history: VecDeque::new(),
+        }
+    }
+
+    // Sample a new point: x = m + sigma B D z
+    fn sample<R: Rng + Sized>(&self, rng: &mut R) -> DVector<f64> {
+        let n = self.mean.len();
+        let z = DVector::from_fn(n, |_, _| sample_normal(rng, 0.0, 1.0));
+        &self.mean + self.sigma * (&self.b * z.component_mul(&self.d))
+    }
+
+    // Update distribution parameters from points sorted from the best to the
+    // worst.
+    fn update(&mut self, sorted: &[DVector<f64>]) {
+        let n = self.mean.len();
+        let nf = n as f64;
+        let old_mean = self.mean.clone();
+
+        self.mean = DVector::zeros(n);
+        for (w, x) in self.weights.iter().zip(sorted) {
+            self.mean += *w * x;
+        }
+        let yw = (&self.mean - &old_mean) / self.sigma;
+
+        // C^-1/2 = B D^-1 B^T
+        let dinv = DMatrix::from_diagonal(&self.d.map(|x| 1.0 / x));
+        let invsqrt_c = &self.b * dinv * self.b.transpose();
+
+        self.generation += 1;
+        self.ps = (1.0 - self.cs) * &self.ps + (self.cs * (2.0 - self.cs) * self.mueff).sqrt() * (invsqrt_c * &yw);
+        let ps_norm = self.ps.norm();
+        let hsig = ps_norm / (1.0 - (1.0 - self.cs).powi(2 * self.generation as i32)).sqrt() / self.chin < 1.4 + 2.0 / (nf + 1.0);
+        let hsig = if hsig { 1.0 } else { 0.0 };
+        self.pc = (1.0 - self.cc) * &self.pc + hsig * (self.cc * (2.0 - self.cc) * self.mueff).sqrt() * &yw;
+
+        // rank-one and rank-mu update
+        let mut rank_mu = DMatrix::zeros(n, n);
+        for (w, x) in self.weights.iter().zip(sorted) {
+            let y = (x - &old_mean) / self.sigma;
+            rank_mu += *w * &y * y.transpose();
+        }
+        let rank_one = &self.pc * self.pc.transpose() + (1.0 - hsig) * self.cc * (2.0 - self.cc) * &self.cov;
+        self.cov = (1.0 - self.c1 - self.cmu) * &self.cov + self.c1 * rank_one + self.cmu * rank_mu;
+        // enforce symmetry
+        self.cov = (&self.cov + self.cov.transpose()) * 0.5;
+
+        self.sigma *= ((self.cs / self.damps) * (ps_norm / self.chin - 1.0)).exp();
+
+        let eigen = self.cov.clone().symmetric_eigen();
+        self.d = eigen.eigenvalues.map(|x| x.max(1e-20).sqrt());
+        self.b = eigen.eigenvectors;
+    }
+
+    fn condition_number(&self) -> f64 {
+        let dmax = self.d.max();
+        let dmin = self.d.min();
+        (dmax / dmin).powi(2)
+    }
+}
+// 8c3f52d0 ends here
+
+// [[file:../spdkit.note::b6e0f4a7][b6e0f4a7]]
+/// Covariance matrix adaptation evolution strategy (CMA-ES) for continuous
+/// optimization on `RealVector` genomes.
+///
+/// The mean of search distribution is initialized as the best genome in seeds
+/// population. The population size (lambda) is set to the default of
+/// 4+3ln(n) unless specified. Sampled points out of bounds are clipped into
+/// bounds before evaluation, while the distribution is updated from the
+/// unclipped samples to avoid biasing the mean and covariance.
+///
+/// # Reference
+///
+/// * Hansen 2016, The CMA Evolution Strategy: A Tutorial
+/// * Auger and Hansen 2005, A Restart CMA Evolution Strategy With Increasing Population Size
+/// * Hansen 2009, Benchmarking a BI-population CMA-ES on the BBOB-2009 function testbed
+///
+#[derive(Debug, Clone)]
+pub struct CmaEs {
+    sigma0: Option<f64>,
+    lambda0: Option<usize>,
+    bounds: Option<Bounds>,
+    restart: CmaRestart,
+    max_restarts: usize,
+    tol_x: f64,
+    tol_fun: f64,
+    max_condition: f64,
+
+    state: Option<CmaState>,
+    x0: Option<DVector<f64>>,
+    nrestarts: usize,
+    // BIPOP: population size and budget of evaluations in each regime
+    lambda_large: usize,
+    evals_large: usize,
+    evals_small: usize,
+    large_regime: bool,
+}
+
+impl Default for CmaEs {
+    fn default() -> Self {
+        Self {
+            sigma0: None,
+            lambda0: None,
+            bounds: None,
+            restart: CmaRestart::default(),
+            max_restarts: 9,
+            tol_x: 1e-12,
+            tol_fun: 1e-12,
+            max_condition: 1e14,
+            state: None,
+            x0: None,
+            nrestarts: 0,
+            lambda_large: 0,
+            evals_large: 0,
+            evals_small: 0,
+            large_regime: true,
+        }
+    }
+}
+
+impl CmaEs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set initial step size. The default is 0.3 of the mean width of bounds
+    /// if available, or 1.0 otherwise.
+    pub fn sigma(mut self, sigma: f64) -> Self {
+        assert!(sigma > 0.0, "invalid step size: {sigma}");
+        self.sigma0 = Some(sigma);
+        self
+    }
+
+    /// Set initial population size (lambda).
+    pub fn population_size(mut self, lambda: usize) -> Self {
+        assert!(lambda >= 2, "invalid population size: {lambda}");
+        self.lambda0 = Some(lambda);
+        self
+    }
+
+    /// Clip sampled points into `bounds`.
+    pub fn bounds(mut self, bounds: Bounds) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Set restart strategy.
+    pub fn restart(mut self, restart: CmaRestart) -> Self {
+        self.restart = restart;
+        self
+    }
+
+    /// Set the max number of restarts. The default is 9.
+    pub fn max_restarts(mut self, n: usize) -> Self {
+        self.max_restarts = n;
+        self
+    }
+
+    /// Restart if step size in all coordinates is less than `tol`. The default is 1e-12.
+    pub fn tolerance_x(mut self, tol: f64) -> Self {
+        self.tol_x = tol;
+        self
+    }
+
+    /// Restart if the range of best objective values in recent generations is
+    /// less than `tol`. The default is 1e-12.
+    pub fn tolerance_fun(mut self, tol: f64) -> Self {
+        self.tol_fun = tol;
+        self
+    }
+
+    /// Return current mean of search distribution.
+    pub fn mean(&self) -> Option<RealVector> {
+        self.state.as_ref().map(|s| s.mean.iter().copied().collect())
+    }
+
+    /// Return current step size.
+    pub fn current_sigma(&self) -> Option<f64> {
+        self.state.as_ref().map(|s| s.sigma)
+    }
+
+    /// Return current condition number of covariance matrix.
+    pub fn condition_number(&self) -> Option<f64> {
+        self.state.as_ref().map(|s| s.condition_number())
+    }
+}
+
+impl CmaEs {
+    fn default_lambda(n: usize) -> usize {
+        4 + (3.0 * (n as f64).ln()).floor() as usize
+    }
+
+    fn default_sigma(&self) -> f64 {
+        self.sigma0.unwrap_or_else(|| match &self.bounds {
+            Some(b) => {
+                let w: f64 = b.upper().iter().zip(b.lower()).map(|(u, l)| u - l).sum();
+                0.3 * w / b.len() as f64
+            }
+            None => 1.0,
+        })
+    }
+
+    fn initialize(&mut self, x0: DVector<f64>) {
+        let lambda = self.lambda0.unwrap_or_else(|| Self::default_lambda(x0.len()));
+        self.lambda_large = lambda;
+        self.state = Some(CmaState::new(x0.clone(), self.default_sigma(), lambda));
+        self.x0 = Some(x0);
+    }
+
+    // Check if current run converged or got stuck.
+    fn should_restart(&self) -> bool {
+        let state = self.state.as_ref().expect("cma-es not initialized");
+        if state.condition_number() > self.max_condition {
+            return true;
+        }
+        if state.sigma * state.d.max() < self.tol_x {
+            return true;
+        }
+        let n = state.mean.len();
+        let nhist = 10 + (30.0 * n as f64 / state.lambda as f64).ceil() as usize;
+        if state.history.len() >= nhist {
+            let fmax = state.history.iter().fmax().unwrap();
+            let fmin = state.history.iter().fmin().unwrap();
+            if fmax - fmin < self.tol_fun {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn start_new_run<R: Rng + Sized>(&mut self, rng: &mut R) {
+        let x0 = match &self.bounds {
+            Some(b) => DVector::from_vec(b.random(rng).to_vec()),
+            None => self.x0.clone().expect("cma-es not initialized"),
+        };
+        let sigma0 = self.default_sigma();
+        let lambda0 = self.lambda0.unwrap_or_else(|| Self::default_lambda(x0.len()));
+
+        let (lambda, sigma) = match self.restart {
+            CmaRestart::None => unreachable!(),
+            CmaRestart::Ipop => {
+                self.lambda_large *= 2;
+                (self.lambda_large, sigma0)
+            }
+            CmaRestart::Bipop => {
+                if self.evals_small < self.evals_large {
+                    self.large_regime = false;
+                    let u: f64 = rng.gen();
+                    let ratio = 0.5 * self.lambda_large as f64 / lambda0 as f64;
+                    let lambda = (lambda0 as f64 * ratio.powf(u * u)).floor() as usize;
+                    (lambda.max(lambda0), sigma0 * 10f64.powf(-2.0 * rng.gen::<f64>()))
+                } else {
+                    self.large_regime = true;
+                    self.lambda_large *= 2;
+                    (self.lambda_large, sigma0)
+                }
+            }
+        };
+        self.nrestarts += 1;
+        info!("CMA-ES restart {} with lambda = {lambda}, sigma = {sigma}", self.nrestarts);
+        self.state = Some(CmaState::new(x0, sigma, lambda));
+    }
+}
+
+impl<F, C> Evolve<RealVector, F, C> for CmaEs
+where
+    F: EvaluateFitness<RealVector>,
+    C: EvaluateObjectiveValue<RealVector>,
+{
+    fn next_generation(
+        &mut self,
+        cur_population: &Population<RealVector>,
+        valuer: &mut Valuer<RealVector, F, C>,
+    ) -> Population<RealVector> {
+        let mut rng = get_rng!();
+
+        if self.state.is_none() {
+            let best = cur_population.best_member().expect("empty population");
+            self.initialize(DVector::from_vec(best.genome().to_vec()));
+        } else if self.restart != CmaRestart::None && self.nrestarts < self.max_restarts && self.should_restart() {
+            self.start_new_run(&mut *rng);
+        }
+        let state = self.state.as_mut().unwrap();
+
+        // 1. sample new points
+        let lambda = state.lambda;
+        let samples: Vec<_> = (0..lambda).map(|_| state.sample(&mut *rng)).collect();
+        let points: Vec<RealVector> = samples
+            .iter()
+            .map(|z| {
+                let mut x: RealVector = z.iter().copied().collect();
+                if let Some(bounds) = &self.bounds {
+                    bounds.clip(&mut x);
+                }
+                x
+            })
+            .collect();
+
+        // 2. evaluate and rank points by fitness
+        let created: HashMap<_, _> = valuer
+            .create_individuals(points.clone())
+            .into_iter()
+            .map(|x| (x.genome().to_owned(), x))
+            .collect();
+        let indvs: Vec<_> = points.iter().filter_map(|x| created.get(x).cloned()).collect();
+        let fitness = valuer.evaluate_fitness(&indvs);
+        let mut order: Vec<_> = (0..indvs.len()).collect();
+        order.sort_by(|&i, &j| float_ordering_maximize(&fitness[i], &fitness[j]));
+
+        // 3. update distribution
+        let sorted: Vec<_> = order.iter().map(|&i| samples[i].clone()).collect();
+        state.update(&sorted);
+        state.history.push_back(indvs[order[0]].objective_value());
+        let n = state.mean.len();
+        let nhist = 10 + (30.0 * n as f64 / lambda as f64).ceil() as usize;
+        while state.history.len() > nhist {
+            state.history.pop_front();
+        }
+        if self.large_regime {
+            self.evals_large += lambda;
+        } else {
+            self.evals_small += lambda;
+        }
+
+        valuer.build_population(indvs).with_size_limit(lambda)
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        if let Some(state) = &self.state {
+            report.set("cma/mean", state.mean.as_slice().to_vec());
+            report.set("cma/sigma", state.sigma);
+            report.set("cma/condition_number", state.condition_number());
+            report.set("cma/population_size", state.lambda);
+            report.set("cma/restarts", self.nrestarts);
+        }
+        report
+    }
+}
+// b6e0f4a7 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn test_cma_es() -> Result<()> {
+        let bounds = Bounds::uniform(4, -2.0, 2.0);
+        let seeds = vec![RealVector::new(vec![-1.0; 4])];
+
+        let algo = CmaEs::new().bounds(bounds.clone());
+        let best = minimize_sphere(algo, &seeds, 301, |g| {
+            let sigma = g.report.get("cma/sigma").and_then(|x| x.as_number());
+            assert!(sigma.is_some_and(|x| x > 0.0) || g.index == 0);
+        })?;
+        assert!(best < 1e-3, "{best}");
+
+        // restarts in IPOP/BIPOP
+        for restart in [CmaRestart::Ipop, CmaRestart::Bipop] {
+            let algo = CmaEs::new().bounds(bounds.clone()).restart(restart).max_restarts(3).tolerance_fun(1e-3);
+            let mut nrestarts = 0;
+            minimize_sphere(algo, &seeds, 301, |g| {
+                if g.index > 0 {
+                    nrestarts = g.report.get("cma/restarts").and_then(|x| x.as_count()).unwrap();
+                    let lambda = g.report.get("cma/population_size").and_then(|x| x.as_count()).unwrap();
+                    assert_eq!(g.population.size_limit(), lambda);
+                }
+            })?;
+            assert!(nrestarts > 0, "{restart:?}");
+        }
+
+        Ok(())
+    }
+}
+// test:1 ends here
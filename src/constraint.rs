@@ -0,0 +1,382 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use crate::common::*;
+use crate::fitness::*;
+use crate::individual::*;
+use crate::population::Population;
+use crate::random::*;
+// imports:1 ends here
+
+// [[file:../spdkit.note::c41e7a2d][c41e7a2d]]
+// Treat unconstrained individual as feasible.
+fn violation_of<G: Genome>(indv: &Individual<G>) -> f64 {
+    indv.constraint_violation().unwrap_or(0.0).max(0.0)
+}
+
+// Subtract penalties from fitness values, and keep the results positive.
+fn penalize<G: Genome>(fitness_values: Vec<f64>, indvs: &[Individual<G>], coefficient: f64) -> Vec<f64> {
+    let values: Vec<_> = fitness_values
+        .into_iter()
+        .zip(indvs)
+        .map(|(f, indv)| f - coefficient * violation_of(indv))
+        .collect();
+
+    match values.iter().fmin() {
+        Some(fmin) if fmin <= 0.0 => {
+            let shift = EPSILON - fmin;
+            values.into_iter().map(|f| f + shift).collect()
+        }
+        _ => values,
+    }
+}
+
+// Assign fitness values from ranks: the first in `order` gets the largest
+// fitness value `n`, the last gets 1.
+fn fitness_from_ranks(order: &[usize]) -> Vec<f64> {
+    let n = order.len();
+    let mut values = vec![0.0; n];
+    for (rank, &i) in order.iter().enumerate() {
+        values[i] = (n - rank) as f64;
+    }
+    values
+}
+// c41e7a2d ends here
+
+// [[file:../spdkit.note::*static penalty][static penalty:1]]
+/// Constraint handling by subtracting a fixed penalty proportional to
+/// constraint violation from the fitness evaluated by an inner fitness
+/// function.
+#[derive(Clone, Debug)]
+pub struct StaticPenalty<F> {
+    fitness: F,
+    coefficient: f64,
+}
+
+impl<F> StaticPenalty<F> {
+    /// Construct with inner `fitness` function and penalty `coefficient`.
+    pub fn new(fitness: F, coefficient: f64) -> Self {
+        assert!(coefficient.is_sign_positive(), "invalid penalty coefficient: {coefficient}");
+        Self { fitness, coefficient }
+    }
+}
+
+impl<G, F> EvaluateFitness<G> for StaticPenalty<F>
+where
+    G: Genome,
+    F: EvaluateFitness<G>,
+{
+    fn evaluate(&mut self, indvs: &[Individual<G>]) -> Vec<f64> {
+        let values = self.fitness.evaluate(indvs);
+        penalize(values, indvs, self.coefficient)
+    }
+
+    fn update(&mut self, population: &Population<G>) {
+        self.fitness.update(population);
+    }
+}
+// static penalty:1 ends here
+
+// [[file:../spdkit.note::*adaptive penalty][adaptive penalty:1]]
+/// Constraint handling with a penalty coefficient adapted from the
+/// feasibility of the best individual in recent generations.
+///
+/// The coefficient is decreased by a factor of `beta1` if the best individual
+/// was feasible in each of the last `nlast` generations, and increased by a
+/// factor of `beta2` if it was infeasible in each of them. The coefficient is
+/// adapted once per generation (see `EvaluateFitness::update`).
+///
+/// # Reference
+///
+/// * Hadj-Alouane and Bean 1997
+///
+#[derive(Clone, Debug)]
+pub struct AdaptivePenalty<F> {
+    fitness: F,
+    coefficient: f64,
+    beta1: f64,
+    beta2: f64,
+    nlast: usize,
+    history: Vec<bool>,
+}
+
+impl<F> AdaptivePenalty<F> {
+    /// Construct with inner `fitness` function and initial penalty
+    /// `coefficient`.
+    pub fn new(fitness: F, coefficient: f64) -> Self {
+        assert!(coefficient > 0.0, "invalid penalty coefficient: {coefficient}");
+        Self {
+            fitness,
+            coefficient,
+            beta1: 2.0,
+            beta2: 3.0,
+            nlast: 5,
+            history: vec![],
+        }
+    }
+
+    /// Set the factors for decreasing (`beta1`) and increasing (`beta2`) the
+    /// penalty coefficient.
+    pub fn factors(mut self, beta1: f64, beta2: f64) -> Self {
+        assert!(beta1 > 1.0 && beta2 > 1.0, "factors should be larger than 1");
+        self.beta1 = beta1;
+        self.beta2 = beta2;
+        self
+    }
+
+    /// The last n generations for adapting penalty coefficient.
+    pub fn nlast(mut self, n: usize) -> Self {
+        assert!(n > 0, "invalid nlast value");
+        self.nlast = n;
+        self
+    }
+
+    /// Return current penalty coefficient.
+    pub fn coefficient(&self) -> f64 {
+        self.coefficient
+    }
+
+    fn record_best(&mut self, best_is_feasible: bool) {
+        self.history.push(best_is_feasible);
+        if self.history.len() > self.nlast {
+            self.history.remove(0);
+        }
+        if self.history.len() == self.nlast {
+            if self.history.iter().all(|&x| x) {
+                self.coefficient /= self.beta1;
+            } else if self.history.iter().all(|&x| !x) {
+                self.coefficient *= self.beta2;
+            }
+        }
+    }
+}
+
+impl<G, F> EvaluateFitness<G> for AdaptivePenalty<F>
+where
+    G: Genome,
+    F: EvaluateFitness<G>,
+{
+    fn evaluate(&mut self, indvs: &[Individual<G>]) -> Vec<f64> {
+        let values = self.fitness.evaluate(indvs);
+        penalize(values, indvs, self.coefficient)
+    }
+
+    fn update(&mut self, population: &Population<G>) {
+        self.fitness.update(population);
+        if let Some(best) = population.best_member() {
+            self.record_best(best.individual.is_feasible());
+        }
+    }
+}
+// adaptive penalty:1 ends here
+
+// [[file:../spdkit.note::*feasibility rules][feasibility rules:1]]
+/// Constraint handling using Deb's feasibility rules:
+///
+/// 1. A feasible individual is preferred to an infeasible one.
+/// 2. Among feasible individuals, the one with larger inner fitness is preferred.
+/// 3. Among infeasible individuals, the one with smaller violation is preferred.
+///
+/// The returned fitness values are derived from the resulting ranks.
+///
+/// # Reference
+///
+/// * Deb 2000
+///
+#[derive(Clone, Debug)]
+pub struct FeasibilityRules<F> {
+    fitness: F,
+}
+
+impl<F> FeasibilityRules<F> {
+    /// Construct with inner `fitness` function for comparing feasible
+    /// individuals.
+    pub fn new(fitness: F) -> Self {
+        Self { fitness }
+    }
+}
+
+impl<G, F> EvaluateFitness<G> for FeasibilityRules<F>
+where
+    G: Genome,
+    F: EvaluateFitness<G>,
+{
+    fn evaluate(&mut self, indvs: &[Individual<G>]) -> Vec<f64> {
+        let values = self.fitness.evaluate(indvs);
+        let violations: Vec<_> = indvs.iter().map(violation_of).collect();
+
+        let mut order: Vec<_> = (0..indvs.len()).collect();
+        order.sort_by(|&i, &j| match (violations[i] > 0.0, violations[j] > 0.0) {
+            (false, false) => float_ordering_maximize(&values[i], &values[j]),
+            (true, true) => float_ordering_minimize(&violations[i], &violations[j]),
+            (false, true) => std::cmp::Ordering::Less,
+            (true, false) => std::cmp::Ordering::Greater,
+        });
+
+        fitness_from_ranks(&order)
+    }
+
+    fn update(&mut self, population: &Population<G>) {
+        self.fitness.update(population);
+    }
+}
+// feasibility rules:1 ends here
+
+// [[file:../spdkit.note::*stochastic ranking][stochastic ranking:1]]
+/// Constraint handling using stochastic ranking, a bubble-sort-like procedure
+/// in which adjacent individuals are compared by inner fitness with
+/// probability `pf` or if both are feasible, and by constraint violation
+/// otherwise.
+///
+/// The returned fitness values are derived from the resulting ranks. The
+/// random comparisons are drawn from a generator owned by the struct, seeded
+/// from the global one at construction.
+///
+/// # Reference
+///
+/// * Runarsson and Yao 2000
+///
+#[derive(Clone, Debug)]
+pub struct StochasticRanking<F> {
+    fitness: F,
+    pf: f64,
+    rng: StdRng,
+}
+
+impl<F> StochasticRanking<F> {
+    /// Construct with inner `fitness` function.
+    pub fn new(fitness: F) -> Self {
+        let rng = StdRng::seed_from_u64(get_rng!().gen());
+        Self { fitness, pf: 0.45, rng }
+    }
+
+    /// Set the probability of comparing infeasible individuals by fitness.
+    pub fn probability(mut self, pf: f64) -> Self {
+        assert!((0.0..=1.0).contains(&pf), "invalid probability: {pf}");
+        self.pf = pf;
+        self
+    }
+}
+
+impl<G, F> EvaluateFitness<G> for StochasticRanking<F>
+where
+    G: Genome,
+    F: EvaluateFitness<G>,
+{
+    fn evaluate(&mut self, indvs: &[Individual<G>]) -> Vec<f64> {
+        let values = self.fitness.evaluate(indvs);
+        let violations: Vec<_> = indvs.iter().map(violation_of).collect();
+
+        let n = indvs.len();
+        let mut order: Vec<_> = (0..n).collect();
+        for _ in 0..n {
+            let mut swapped = false;
+            for k in 1..n {
+                let (i, j) = (order[k - 1], order[k]);
+                let feasible = violations[i] <= 0.0 && violations[j] <= 0.0;
+                let swap = if feasible || self.rng.gen::<f64>() < self.pf {
+                    values[i] < values[j]
+                } else {
+                    violations[i] > violations[j]
+                };
+                if swap {
+                    order.swap(k - 1, k);
+                    swapped = true;
+                }
+            }
+            if !swapped {
+                break;
+            }
+        }
+
+        fitness_from_ranks(&order)
+    }
+
+    fn update(&mut self, population: &Population<G>) {
+        self.fitness.update(population);
+    }
+}
+// stochastic ranking:1 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::encoding::Binary;
+
+    // OneMax with at most 3 ones allowed.
+    #[derive(Clone, Debug)]
+    struct ConstrainedOneMax;
+
+    impl EvaluateObjectiveValue<Binary> for ConstrainedOneMax {
+        fn evaluate(&self, genome: &Binary) -> f64 {
+            OneMax.evaluate(genome)
+        }
+
+        fn evaluate_violation(&self, genome: &Binary) -> Option<f64> {
+            let v = OneMax.evaluate(genome) - 3.0;
+            Some(v.max(0.0))
+        }
+    }
+
+    #[test]
+    fn test_constraint_handling() {
+        let genomes: Vec<_> = ["11111", "11100", "11000", "11110"]
+            .iter()
+            .map(|x| Binary::from_str(x))
+            .collect();
+        let indvs: Vec<_> = genomes
+            .into_iter()
+            .map(|g| Individual::new(g, &mut ConstrainedOneMax))
+            .collect();
+        assert!(!indvs[0].is_feasible());
+        assert!(indvs[1].is_feasible());
+
+        let values = StaticPenalty::new(Maximize, 10.0).evaluate(&indvs);
+        assert!(values.iter().all(|&f| f > 0.0));
+        assert_eq!(values.iter().imax().unwrap().0, 1);
+
+        let values = FeasibilityRules::new(Maximize).evaluate(&indvs);
+        assert_eq!(values, vec![1.0, 4.0, 3.0, 2.0]);
+
+        let values = StochasticRanking::new(Maximize).probability(0.0).evaluate(&indvs);
+        assert_eq!(values, vec![1.0, 4.0, 3.0, 2.0]);
+
+        // adapted per generation, not per evaluation
+        let mut penalty = AdaptivePenalty::new(Maximize, 0.1).nlast(2);
+        let population = Population::build(indvs, &mut penalty);
+        penalty.evaluate(population.individuals());
+        assert_eq!(penalty.coefficient(), 0.1);
+        penalty.update(&population);
+        assert_eq!(penalty.coefficient(), 0.1);
+        penalty.update(&population);
+        assert!(penalty.coefficient() > 0.1);
+    }
+
+    #[test]
+    fn test_stochastic_ranking_engine() -> Result<()> {
+        use crate::engine::*;
+        use crate::gears::*;
+        use crate::operators::selection::RouletteWheelSelection;
+        use crate::operators::variation::OnePointCrossOver;
+
+        let valuer = Valuer::new()
+            .with_fitness(StochasticRanking::new(Maximize))
+            .with_creator(ConstrainedOneMax);
+        let breeder = GeneticBreeder::new()
+            .with_crossover(OnePointCrossOver)
+            .with_selector(RouletteWheelSelection::new(2));
+        let algo = EvolutionAlgorithm::new(breeder, Survivor::default());
+
+        let seeds: Vec<_> = ["11111", "11100", "11000", "11110", "10101", "00111"]
+            .iter()
+            .map(|x| Binary::from_str(x))
+            .collect();
+        let mut engine = Engine::create().valuer(valuer).algorithm(algo);
+        for g in engine.evolve(&seeds).take(5) {
+            let generation = g?;
+            assert!(generation.population.best_member().is_some());
+        }
+
+        Ok(())
+    }
+}
+// test:1 ends here
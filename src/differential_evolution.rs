@@ -0,0 +1,319 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use std::collections::HashMap;
+
+use crate::common::*;
+use crate::encoding::{Bounds, RealVector};
+use crate::engine::Evolve;
+use crate::fitness::*;
+use crate::gears::Valuer;
+use crate::individual::*;
+use crate::population::*;
+use crate::random::*;
+use crate::report::Report;
+// imports:1 ends here
+
+// [[file:../spdkit.note::4d7a1e38][4d7a1e38]]
+/// Mutation strategies of differential evolution. A binomial crossover is
+/// applied after mutation for all strategies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DeStrategy {
+    /// DE/rand/1/bin: v = x_r1 + F (x_r2 - x_r3)
+    #[default]
+    RandOneBin,
+    /// DE/best/1/bin: v = x_best + F (x_r1 - x_r2)
+    BestOneBin,
+    /// DE/current-to-best/1: v = x_i + F (x_best - x_i) + F (x_r1 - x_r2)
+    CurrentToBestOne,
+}
+
+/// Adaptation of control parameters F and CR in differential evolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DeAdaptation {
+    /// Use fixed F and CR.
+    #[default]
+    Fixed,
+    /// Self-adaptive F and CR carried by each individual (jDE).
+    Jde,
+    /// F and CR sampled around means learned from successful trials (JADE).
+    Jade,
+}
+
+/// Differential evolution for continuous optimization on `RealVector`
+/// genomes.
+///
+/// # Reference
+///
+/// * Storn and Price 1997, Differential Evolution – A Simple and Efficient Heuristic for global Optimization over Continuous Spaces
+/// * Brest et al. 2006, Self-Adapting Control Parameters in Differential Evolution
+/// * Zhang and Sanderson 2009, JADE: Adaptive Differential Evolution With Optional External Archive
+///
+#[derive(Debug, Clone)]
+pub struct DifferentialEvolution {
+    strategy: DeStrategy,
+    adaptation: DeAdaptation,
+    f: f64,
+    cr: f64,
+    bounds: Option<Bounds>,
+
+    // jDE: control parameters carried by each individual
+    params: HashMap<RealVector, (f64, f64)>,
+    // JADE: the means of F and CR, and the learning rate
+    mu_f: f64,
+    mu_cr: f64,
+    c: f64,
+
+    // statistics of last generation
+    nsuccess: usize,
+    ntrials: usize,
+    mean_f: f64,
+    mean_cr: f64,
+}
+
+impl Default for DifferentialEvolution {
+    fn default() -> Self {
+        Self {
+            strategy: DeStrategy::default(),
+            adaptation: DeAdaptation::default(),
+            f: 0.5,
+            cr: 0.9,
+            bounds: None,
+            params: HashMap::new(),
+            mu_f: 0.5,
+            mu_cr: 0.5,
+            c: 0.1,
+            nsuccess: 0,
+            ntrials: 0,
+            mean_f: 0.0,
+            mean_cr: 0.0,
+        }
+    }
+}
+
+impl DifferentialEvolution {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set mutation strategy.
+    pub fn strategy(mut self, strategy: DeStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Set adaptation scheme of control parameters.
+    pub fn adaptation(mut self, adaptation: DeAdaptation) -> Self {
+        self.adaptation = adaptation;
+        self
+    }
+
+    /// Set the scale factor F. The default is 0.5. For jDE, this is the
+    /// initial value for each individual.
+    pub fn scale_factor(mut self, f: f64) -> Self {
+        assert!(f > 0.0, "invalid scale factor: {f}");
+        self.f = f;
+        self
+    }
+
+    /// Set the crossover rate CR. The default is 0.9. For jDE, this is the
+    /// initial value for each individual.
+    pub fn crossover_rate(mut self, cr: f64) -> Self {
+        assert!((0.0..=1.0).contains(&cr), "invalid crossover rate: {cr}");
+        self.cr = cr;
+        self
+    }
+
+    /// Clip trial vectors into `bounds`.
+    pub fn bounds(mut self, bounds: Bounds) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+}
+
+impl DifferentialEvolution {
+    // Sample control parameters F and CR for `target`.
+    fn sample_params<R: Rng + Sized>(&self, target: &RealVector, rng: &mut R) -> (f64, f64) {
+        match self.adaptation {
+            DeAdaptation::Fixed => (self.f, self.cr),
+            DeAdaptation::Jde => {
+                let (mut f, mut cr) = self.params.get(target).copied().unwrap_or((self.f, self.cr));
+                if rng.gen::<f64>() < 0.1 {
+                    f = 0.1 + 0.9 * rng.gen::<f64>();
+                }
+                if rng.gen::<f64>() < 0.1 {
+                    cr = rng.gen::<f64>();
+                }
+                (f, cr)
+            }
+            DeAdaptation::Jade => {
+                let cr = sample_normal(rng, self.mu_cr, 0.1).clamp(0.0, 1.0);
+                let f = loop {
+                    let f = sample_cauchy(rng, self.mu_f, 0.1);
+                    if f > 0.0 {
+                        break f.min(1.0);
+                    }
+                };
+                (f, cr)
+            }
+        }
+    }
+
+    // Create a trial vector for the `i`-th individual in `genomes`.
+    fn create_trial<R: Rng + Sized>(
+        &self,
+        i: usize,
+        ibest: usize,
+        genomes: &[&RealVector],
+        (f, cr): (f64, f64),
+        rng: &mut R,
+    ) -> RealVector {
+        let candidates: Vec<_> = (0..genomes.len()).filter(|&j| j != i).collect();
+        let r: Vec<_> = candidates.choose_multiple(rng, 3).map(|&j| genomes[j]).collect();
+        let (x, xb) = (genomes[i], genomes[ibest]);
+
+        let d = x.len();
+        let mutant: Vec<_> = (0..d)
+            .map(|k| match self.strategy {
+                DeStrategy::RandOneBin => r[0][k] + f * (r[1][k] - r[2][k]),
+                DeStrategy::BestOneBin => xb[k] + f * (r[0][k] - r[1][k]),
+                DeStrategy::CurrentToBestOne => x[k] + f * (xb[k] - x[k]) + f * (r[0][k] - r[1][k]),
+            })
+            .collect();
+
+        // binomial crossover
+        let jrand = rng.gen_range(0..d);
+        let mut trial: RealVector = (0..d)
+            .map(|k| if k == jrand || rng.gen::<f64>() < cr { mutant[k] } else { x[k] })
+            .collect();
+        if let Some(bounds) = &self.bounds {
+            bounds.clip(&mut trial);
+        }
+        trial
+    }
+
+    // Update JADE means from control parameters of successful trials.
+    fn adapt(&mut self, success: &[(f64, f64)]) {
+        if self.adaptation != DeAdaptation::Jade || success.is_empty() {
+            return;
+        }
+        let n = success.len() as f64;
+        let mean_cr = success.iter().map(|(_, cr)| cr).sum::<f64>() / n;
+        // Lehmer mean of F
+        let sf: f64 = success.iter().map(|(f, _)| f).sum();
+        let sf2: f64 = success.iter().map(|(f, _)| f * f).sum();
+        self.mu_cr = (1.0 - self.c) * self.mu_cr + self.c * mean_cr;
+        self.mu_f = (1.0 - self.c) * self.mu_f + self.c * sf2 / sf;
+    }
+}
+
+impl<F, C> Evolve<RealVector, F, C> for DifferentialEvolution
+where
+    F: EvaluateFitness<RealVector>,
+    C: EvaluateObjectiveValue<RealVector>,
+{
+    fn next_generation(
+        &mut self,
+        cur_population: &Population<RealVector>,
+        valuer: &mut Valuer<RealVector, F, C>,
+    ) -> Population<RealVector> {
+        let mut rng = get_rng!();
+
+        let targets = cur_population.individuals();
+        let n = targets.len();
+        assert!(n >= 4, "differential evolution requires at least 4 individuals.");
+        let genomes: Vec<_> = targets.iter().map(|x| x.genome()).collect();
+        let (ibest, _) = cur_population
+            .members()
+            .map(|m| m.fitness_value())
+            .imax()
+            .expect("empty population");
+
+        // 1. create trial vectors
+        let params: Vec<_> = genomes.iter().map(|g| self.sample_params(g, &mut *rng)).collect();
+        let trials: Vec<_> = (0..n)
+            .map(|i| self.create_trial(i, ibest, &genomes, params[i], &mut *rng))
+            .collect();
+
+        // 2. evaluate trial vectors. Individual creation may drop duplicates
+        // and change the order, so we match them by genome.
+        let created: HashMap<_, _> = valuer
+            .create_individuals(trials.clone())
+            .into_iter()
+            .map(|x| (x.genome().to_owned(), x))
+            .collect();
+        let mut combined = targets.to_vec();
+        let mut matched = vec![];
+        for (i, trial) in trials.iter().enumerate() {
+            if let Some(indv) = created.get(trial) {
+                combined.push(indv.to_owned());
+                matched.push(i);
+            }
+        }
+
+        // 3. one-to-one selection between target and trial vectors
+        let fitness = valuer.evaluate_fitness(&combined);
+        let mut survived = targets.to_vec();
+        let mut new_params = HashMap::new();
+        let mut success = vec![];
+        for (k, &i) in matched.iter().enumerate() {
+            if fitness[n + k] >= fitness[i] {
+                survived[i] = combined[n + k].clone();
+                success.push(params[i]);
+                new_params.insert(trials[i].clone(), params[i]);
+            } else if let Some(&p) = self.params.get(genomes[i]) {
+                new_params.insert(genomes[i].clone(), p);
+            }
+        }
+        self.params = new_params;
+        self.adapt(&success);
+
+        self.nsuccess = success.len();
+        self.ntrials = n;
+        self.mean_f = params.iter().map(|(f, _)| f).sum::<f64>() / n as f64;
+        self.mean_cr = params.iter().map(|(_, cr)| cr).sum::<f64>() / n as f64;
+        info!("{} of {} trial vectors succeeded.", self.nsuccess, n);
+
+        valuer.build_population(survived).with_size_limit(cur_population.size_limit())
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        if self.ntrials > 0 {
+            report.set("de/success_rate", self.nsuccess as f64 / self.ntrials as f64);
+            report.set("de/scale_factor", self.mean_f);
+            report.set("de/crossover_rate", self.mean_cr);
+        }
+        report
+    }
+}
+// 4d7a1e38 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn test_differential_evolution() -> Result<()> {
+        let bounds = Bounds::uniform(5, -5.0, 5.0);
+        let seeds: Vec<_> = {
+            let mut rng = get_rng!();
+            (0..30).map(|_| bounds.random(&mut *rng)).collect()
+        };
+        for strategy in [DeStrategy::RandOneBin, DeStrategy::BestOneBin, DeStrategy::CurrentToBestOne] {
+            for adaptation in [DeAdaptation::Fixed, DeAdaptation::Jde, DeAdaptation::Jade] {
+                let algo = DifferentialEvolution::new()
+                    .strategy(strategy)
+                    .adaptation(adaptation)
+                    .bounds(bounds.clone());
+                let best = minimize_sphere(algo, &seeds, 200, |g| {
+                    assert!(g.report.get("de/success_rate").is_some() || g.index == 0);
+                })?;
+                assert!(best < 1e-2, "{strategy:?} {adaptation:?}: {best}");
+            }
+        }
+
+        Ok(())
+    }
+}
+// test:1 ends here
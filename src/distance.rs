@@ -0,0 +1,233 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use std::borrow::Borrow;
+
+use gchemol::Molecule;
+
+use crate::common::*;
+use crate::encoding::*;
+use crate::fingerprint::FingerPrintExt;
+use crate::individual::Genome;
+use crate::population::Population;
+use crate::similarity::SimilarityExt;
+// imports:1 ends here
+
+// [[file:../spdkit.note::7d2f0c94][7d2f0c94]]
+/// Measure the distance between two genomes. The smaller of the distance, the
+/// more similar of the two genomes.
+///
+/// Any closure in the form of `Fn(&G, &G) -> f64` can be used as a distance.
+pub trait GenomeDistance<G> {
+    fn distance(&self, a: &G, b: &G) -> f64;
+}
+
+impl<G, T> GenomeDistance<G> for T
+where
+    T: Fn(&G, &G) -> f64,
+{
+    fn distance(&self, a: &G, b: &G) -> f64 {
+        self(a, b)
+    }
+}
+// 7d2f0c94 ends here
+
+// [[file:../spdkit.note::*hamming][hamming:1]]
+/// The number of positions at which the corresponding bits are different.
+#[derive(Debug, Clone, Copy)]
+pub struct Hamming;
+
+impl GenomeDistance<Binary> for Hamming {
+    fn distance(&self, a: &Binary, b: &Binary) -> f64 {
+        assert_eq!(a.len(), b.len(), "genomes differ in length!");
+        a.iter().zip(b.iter()).filter(|(x, y)| x != y).count() as f64
+    }
+}
+// hamming:1 ends here
+
+// [[file:../spdkit.note::*real vector][real vector:1]]
+/// Euclidean (L2) distance between two real vectors.
+#[derive(Debug, Clone, Copy)]
+pub struct Euclidean;
+
+impl GenomeDistance<RealVector> for Euclidean {
+    fn distance(&self, a: &RealVector, b: &RealVector) -> f64 {
+        assert_eq!(a.len(), b.len(), "genomes differ in length!");
+        a.iter().zip(b.iter()).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt()
+    }
+}
+
+/// Manhattan (L1) distance between two real vectors.
+#[derive(Debug, Clone, Copy)]
+pub struct Manhattan;
+
+impl GenomeDistance<RealVector> for Manhattan {
+    fn distance(&self, a: &RealVector, b: &RealVector) -> f64 {
+        assert_eq!(a.len(), b.len(), "genomes differ in length!");
+        a.iter().zip(b.iter()).map(|(x, y)| (x - y).abs()).sum()
+    }
+}
+// real vector:1 ends here
+
+// [[file:../spdkit.note::*permutation][permutation:1]]
+/// Kendall tau distance: the number of pairs of elements ordered differently
+/// in two permutations, which equals the minimum number of adjacent swaps
+/// transforming one into the other.
+#[derive(Debug, Clone, Copy)]
+pub struct KendallTau;
+
+impl GenomeDistance<Permutation> for KendallTau {
+    fn distance(&self, a: &Permutation, b: &Permutation) -> f64 {
+        assert_eq!(a.len(), b.len(), "genomes differ in length!");
+        // positions of elements of `a` in `b`
+        let pos = b.inverse();
+        let x: Vec<_> = a.iter().map(|&e| pos[e]).collect();
+        let n = x.len();
+        (0..n).map(|i| (i + 1..n).filter(|&j| x[i] > x[j]).count()).sum::<usize>() as f64
+    }
+}
+
+/// Swap distance: the minimum number of swaps of any two elements
+/// transforming one permutation into the other, i.e. n minus the number of
+/// cycles.
+#[derive(Debug, Clone, Copy)]
+pub struct SwapDistance;
+
+impl GenomeDistance<Permutation> for SwapDistance {
+    fn distance(&self, a: &Permutation, b: &Permutation) -> f64 {
+        assert_eq!(a.len(), b.len(), "genomes differ in length!");
+        let pos = b.inverse();
+        let x: Vec<_> = a.iter().map(|&e| pos[e]).collect();
+        let mut visited = vec![false; x.len()];
+        let mut ncycles = 0;
+        for i in 0..x.len() {
+            if !visited[i] {
+                ncycles += 1;
+                let mut j = i;
+                while !visited[j] {
+                    visited[j] = true;
+                    j = x[j];
+                }
+            }
+        }
+        (x.len() - ncycles) as f64
+    }
+}
+// permutation:1 ends here
+
+// [[file:../spdkit.note::*molecule][molecule:1]]
+/// Disparity of principal moments of inertia between two molecules, using the
+/// algorithm proposed by Lazauskas et al (DOI:10.1039/C6NR09072A).
+#[derive(Debug, Clone, Copy)]
+pub struct InertiaDisparity;
+
+impl<G> GenomeDistance<G> for InertiaDisparity
+where
+    G: Borrow<Molecule>,
+{
+    fn distance(&self, a: &G, b: &G) -> f64 {
+        a.borrow().disparity_between(b.borrow())
+    }
+}
+
+/// Zero if two molecules have the same bond graph (as identified by
+/// `FingerPrintExt::fingerprint`), otherwise one.
+#[derive(Debug, Clone, Copy)]
+pub struct FingerprintDistance;
+
+impl<G> GenomeDistance<G> for FingerprintDistance
+where
+    G: Borrow<Molecule>,
+{
+    fn distance(&self, a: &G, b: &G) -> f64 {
+        if a.borrow().fingerprint() == b.borrow().fingerprint() {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Root-mean-square deviation of atom positions after superimposing one
+/// molecule onto the other.
+///
+/// # NOTE
+///
+/// * The atoms in two molecules should be in the same order.
+#[derive(Debug, Clone, Copy)]
+pub struct Rmsd;
+
+impl<G> GenomeDistance<G> for Rmsd
+where
+    G: Borrow<Molecule>,
+{
+    fn distance(&self, a: &G, b: &G) -> f64 {
+        let mut mol = a.borrow().clone();
+        mol.superimpose_onto(b.borrow(), None)
+    }
+}
+// molecule:1 ends here
+
+// [[file:../spdkit.note::*pairwise][pairwise:1]]
+/// Compute pairwise distances between `genomes` in parallel.
+pub fn pairwise_distances<G, D>(genomes: &[&G], metric: &D) -> Vec<Vec<f64>>
+where
+    G: Sync,
+    D: GenomeDistance<G> + Sync,
+{
+    let n = genomes.len();
+    let pairs: Vec<_> = (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j))).collect();
+    let values: Vec<_> = pairs
+        .par_iter()
+        .map(|&(i, j)| metric.distance(genomes[i], genomes[j]))
+        .collect();
+
+    let mut dm = vec![vec![0.0; n]; n];
+    for (&(i, j), d) in pairs.iter().zip(values) {
+        dm[i][j] = d;
+        dm[j][i] = d;
+    }
+    dm
+}
+
+/// Compute distance matrix between genomes of all individuals in
+/// `population` in parallel.
+pub fn distance_matrix<G, D>(population: &Population<G>, metric: &D) -> Vec<Vec<f64>>
+where
+    G: Genome + Sync,
+    D: GenomeDistance<G> + Sync,
+{
+    let genomes: Vec<_> = population.individuals().iter().map(|x| x.genome()).collect();
+    pairwise_distances(&genomes, metric)
+}
+// pairwise:1 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[test]
+fn test_genome_distance() {
+    let a = Binary::from_str("10110");
+    let b = Binary::from_str("00111");
+    assert_eq!(Hamming.distance(&a, &b), 2.0);
+    assert_eq!(Hamming.distance(&a, &a), 0.0);
+
+    let d = |a: &f64, b: &f64| (a - b).abs();
+    assert_eq!(d.distance(&1.0, &3.5), 2.5);
+
+    let a = RealVector::new(vec![0.0, 3.0]);
+    let b = RealVector::new(vec![4.0, 0.0]);
+    assert_eq!(Euclidean.distance(&a, &b), 5.0);
+    assert_eq!(Manhattan.distance(&a, &b), 7.0);
+
+    let a = Permutation::new(vec![0, 1, 2, 3]);
+    let b = Permutation::new(vec![1, 0, 3, 2]);
+    let c = Permutation::new(vec![3, 2, 1, 0]);
+    assert_eq!(KendallTau.distance(&a, &b), 2.0);
+    assert_eq!(KendallTau.distance(&a, &c), 6.0);
+    assert_eq!(SwapDistance.distance(&a, &b), 2.0);
+    assert_eq!(SwapDistance.distance(&a, &c), 2.0);
+    assert_eq!(SwapDistance.distance(&c, &c), 0.0);
+
+    let genomes = [&a, &b, &c];
+    let dm = pairwise_distances(&genomes, &KendallTau);
+    assert_eq!(dm[0], vec![0.0, 2.0, 6.0]);
+    assert_eq!(dm[2][1], dm[1][2]);
+}
+// test:1 ends here
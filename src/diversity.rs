@@ -0,0 +1,186 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use std::borrow::Borrow;
+use std::collections::HashSet;
+
+use gchemol::Molecule;
+
+use crate::distance::{distance_matrix, GenomeDistance};
+use crate::encoding::Binary;
+use crate::fingerprint::FingerPrintExt;
+use crate::individual::*;
+use crate::population::*;
+// imports:1 ends here
+
+// [[file:../spdkit.note::b5f3e8d1][b5f3e8d1]]
+impl<G: Genome> Population<G> {
+    /// Return the coefficient of variation (standard deviation divided by
+    /// the absolute mean) of objective values of members. Objective values
+    /// are used since fitness values are relative to population. Return zero
+    /// if the mean is zero.
+    ///
+    /// The measure assumes objective values of one sign, e.g. all energies
+    /// negative; it is meaningless if they change sign.
+    pub fn objective_cv(&self) -> f64 {
+        let values: Vec<_> = self.members().map(|m| m.objective_value()).collect();
+        if values.is_empty() {
+            return 0.0;
+        }
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        if mean == 0.0 {
+            return 0.0;
+        }
+        let var = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        var.sqrt() / mean.abs()
+    }
+
+    /// Return the mean pairwise distance between genomes of members using
+    /// `metric`.
+    pub fn mean_distance<D>(&self, metric: &D) -> f64
+    where
+        G: Sync,
+        D: GenomeDistance<G> + Sync,
+    {
+        let n = self.size();
+        if n < 2 {
+            return 0.0;
+        }
+        let dm = distance_matrix(self, metric);
+        let total: f64 = dm.iter().flatten().sum();
+        total / (n * (n - 1)) as f64
+    }
+}
+
+impl Population<Binary> {
+    /// Return the Shannon entropy (in bits) of each locus over members. A
+    /// locus fixed in all members has zero entropy.
+    pub fn locus_entropy(&self) -> Vec<f64> {
+        let indvs = self.individuals();
+        let n = indvs.len() as f64;
+        let nbits = indvs.first().map(|x| x.genome().len()).unwrap_or(0);
+        (0..nbits)
+            .map(|i| {
+                let p = indvs.iter().filter(|x| x.genome()[i]).count() as f64 / n;
+                [p, 1.0 - p].iter().filter(|&&x| x > 0.0).map(|x| -x * x.log2()).sum()
+            })
+            .collect()
+    }
+}
+
+impl<G> Population<G>
+where
+    G: Genome + Borrow<Molecule>,
+{
+    /// Return the number of unique fingerprints of molecules in members.
+    pub fn unique_fingerprints(&self) -> usize {
+        let fingerprints: HashSet<_> = self.individuals().iter().map(|x| x.genome().borrow().fingerprint()).collect();
+        fingerprints.len()
+    }
+}
+
+/// A measure of population diversity, reported in each `Generation` as
+/// "diversity/<name>".
+pub trait Diversity<G: Genome> {
+    /// The name of the measure.
+    fn name(&self) -> &str;
+
+    /// Measure diversity of `population`.
+    fn measure(&self, population: &Population<G>) -> f64;
+}
+
+/// The coefficient of variation of objective values, assuming they are of
+/// one sign.
+#[derive(Debug, Clone, Copy)]
+pub struct ObjectiveCv;
+
+impl<G: Genome> Diversity<G> for ObjectiveCv {
+    fn name(&self) -> &str {
+        "objective_cv"
+    }
+
+    fn measure(&self, population: &Population<G>) -> f64 {
+        population.objective_cv()
+    }
+}
+
+/// The mean pairwise distance between genomes.
+#[derive(Debug, Clone, Copy)]
+pub struct MeanDistance<D>(pub D);
+
+impl<G, D> Diversity<G> for MeanDistance<D>
+where
+    G: Genome + Sync,
+    D: GenomeDistance<G> + Sync,
+{
+    fn name(&self) -> &str {
+        "mean_distance"
+    }
+
+    fn measure(&self, population: &Population<G>) -> f64 {
+        population.mean_distance(&self.0)
+    }
+}
+
+/// The mean entropy over loci of `Binary` genomes.
+#[derive(Debug, Clone, Copy)]
+pub struct LocusEntropy;
+
+impl Diversity<Binary> for LocusEntropy {
+    fn name(&self) -> &str {
+        "locus_entropy"
+    }
+
+    fn measure(&self, population: &Population<Binary>) -> f64 {
+        let entropy = population.locus_entropy();
+        if entropy.is_empty() {
+            0.0
+        } else {
+            entropy.iter().sum::<f64>() / entropy.len() as f64
+        }
+    }
+}
+
+/// The number of unique fingerprints of molecules.
+#[derive(Debug, Clone, Copy)]
+pub struct UniqueFingerprints;
+
+impl<G> Diversity<G> for UniqueFingerprints
+where
+    G: Genome + Borrow<Molecule>,
+{
+    fn name(&self) -> &str {
+        "unique_fingerprints"
+    }
+
+    fn measure(&self, population: &Population<G>) -> f64 {
+        population.unique_fingerprints() as f64
+    }
+}
+// b5f3e8d1 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[test]
+fn test_diversity() {
+    use crate::distance::Hamming;
+    use crate::fitness::Maximize;
+    use vecfx::approx::assert_relative_eq;
+
+    let genomes: Vec<_> = ["1100", "1010", "1001"].iter().map(|x| Binary::from_str(x)).collect();
+    let population = Population::build(OneMax.create(genomes), &mut Maximize);
+
+    let entropy = population.locus_entropy();
+    assert_eq!(entropy[0], 0.0);
+    assert_relative_eq!(entropy[1], 0.9183, epsilon = 1e-4);
+    assert_eq!(population.mean_distance(&Hamming), 2.0);
+    assert_eq!(MeanDistance(Hamming).measure(&population), 2.0);
+    assert_relative_eq!(LocusEntropy.measure(&population), 0.9183 * 3.0 / 4.0, epsilon = 1e-4);
+    // all members have the same objective value
+    assert_eq!(population.objective_cv(), 0.0);
+
+    // objective values of 4 and 1: mean 2.5, standard deviation 1.5
+    let genomes: Vec<_> = ["1111", "1000"].iter().map(|x| Binary::from_str(x)).collect();
+    let population = Population::build(OneMax.create(genomes), &mut Maximize);
+    assert_relative_eq!(population.objective_cv(), 0.6, epsilon = 1e-8);
+    assert_relative_eq!(ObjectiveCv.measure(&population), 0.6, epsilon = 1e-8);
+}
+// test:1 ends here
@@ -68,6 +68,196 @@ impl Binary {
 }
 // e2e7a684 ends here
 
+// [[file:../spdkit.note::6f0c2a91][6f0c2a91]]
+/// A real-valued genome for continuous optimization.
+///
+/// Two genomes are equal only if all their components are bitwise identical.
+#[derive(Clone, Debug, Default)]
+pub struct RealVector(Vec<f64>);
+
+impl crate::individual::Genome for RealVector {}
+
+impl PartialEq for RealVector {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len() && self.0.iter().zip(&other.0).all(|(a, b)| a.to_bits() == b.to_bits())
+    }
+}
+
+impl Eq for RealVector {}
+
+impl std::hash::Hash for RealVector {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        for x in self.0.iter() {
+            x.to_bits().hash(state);
+        }
+    }
+}
+
+// Print components separated by whitespace.
+impl Display for RealVector {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        let v: Vec<_> = self.0.iter().map(|x| format!("{:.6}", x)).collect();
+        write!(f, "{}", v.join(" "))
+    }
+}
+
+impl FromIterator<f64> for RealVector {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = f64>,
+    {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl std::ops::Deref for RealVector {
+    type Target = Vec<f64>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for RealVector {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl RealVector {
+    /// Construct from a list of f64.
+    pub fn new(list: Vec<f64>) -> Self {
+        Self(list)
+    }
+}
+
+/// Lower and upper bounds for each component of `RealVector`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bounds {
+    lower: Vec<f64>,
+    upper: Vec<f64>,
+}
+
+impl Bounds {
+    /// Construct from `lower` and `upper` bounds of each component.
+    pub fn new(lower: Vec<f64>, upper: Vec<f64>) -> Self {
+        assert_eq!(lower.len(), upper.len(), "bounds size mismatch");
+        assert!(lower.iter().zip(&upper).all(|(l, u)| l <= u), "invalid bounds");
+        Self { lower, upper }
+    }
+
+    /// Construct with the same bounds for all `n` components.
+    pub fn uniform(n: usize, lower: f64, upper: f64) -> Self {
+        Self::new(vec![lower; n], vec![upper; n])
+    }
+
+    /// Return the number of components.
+    pub fn len(&self) -> usize {
+        self.lower.len()
+    }
+
+    /// Return true if there is no component.
+    pub fn is_empty(&self) -> bool {
+        self.lower.is_empty()
+    }
+
+    /// Return the lower bounds.
+    pub fn lower(&self) -> &[f64] {
+        &self.lower
+    }
+
+    /// Return the upper bounds.
+    pub fn upper(&self) -> &[f64] {
+        &self.upper
+    }
+
+    /// Clip components of `x` into bounds.
+    pub fn clip(&self, x: &mut RealVector) {
+        assert_eq!(x.len(), self.len(), "genome size mismatch");
+        for (xi, (l, u)) in x.iter_mut().zip(self.lower.iter().zip(&self.upper)) {
+            *xi = xi.clamp(*l, *u);
+        }
+    }
+
+    /// Sample a genome uniformly within bounds.
+    pub fn random<R: Rng + Sized>(&self, rng: &mut R) -> RealVector {
+        self.lower
+            .iter()
+            .zip(&self.upper)
+            .map(|(l, u)| l + (u - l) * rng.gen::<f64>())
+            .collect()
+    }
+}
+// 6f0c2a91 ends here
+
+// [[file:../spdkit.note::4b7e90c3][4b7e90c3]]
+/// A permutation of 0..n, e.g. the order of cities or the assignment of atoms
+/// to sites.
+#[derive(Clone, Hash, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Permutation(Vec<usize>);
+
+impl crate::individual::Genome for Permutation {}
+
+// Print elements separated by whitespace.
+impl Display for Permutation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        let v: Vec<_> = self.0.iter().map(|x| x.to_string()).collect();
+        write!(f, "{}", v.join(" "))
+    }
+}
+
+impl std::ops::Deref for Permutation {
+    type Target = Vec<usize>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Permutation {
+    /// Construct from a list of 0..n in any order.
+    ///
+    /// # Panics
+    ///
+    /// * panics if `list` is not a permutation of 0..n.
+    pub fn new(list: Vec<usize>) -> Self {
+        let n = list.len();
+        let mut seen = vec![false; n];
+        for &i in list.iter() {
+            assert!(i < n && !seen[i], "invalid permutation: {list:?}");
+            seen[i] = true;
+        }
+        Self(list)
+    }
+
+    /// The identity permutation of 0..n.
+    pub fn identity(n: usize) -> Self {
+        Self((0..n).collect())
+    }
+
+    /// Generate a random permutation of 0..n.
+    pub fn random<R: Rng + Sized>(n: usize, rng: &mut R) -> Self {
+        let mut list: Vec<_> = (0..n).collect();
+        list.shuffle(rng);
+        Self(list)
+    }
+
+    /// Swap elements at position `i` and `j`.
+    pub fn swap(&mut self, i: usize, j: usize) {
+        self.0.swap(i, j);
+    }
+
+    /// Return the inverse permutation.
+    pub fn inverse(&self) -> Self {
+        let mut inv = vec![0; self.0.len()];
+        for (i, &x) in self.0.iter().enumerate() {
+            inv[x] = i;
+        }
+        Self(inv)
+    }
+}
+// 4b7e90c3 ends here
+
 // [[file:../spdkit.note::9defdebe][9defdebe]]
 // impl crate::individual::Genome for gchemol::Molecule {}
 // 9defdebe ends here
@@ -86,6 +276,19 @@ impl Mutate for Binary {
         self.flip(positions);
     }
 }
+
+impl Mutate for Permutation {
+    /// Swap `n` pairs of elements randomly.
+    fn mutate<R: Rng + Sized>(&mut self, n: usize, rng: &mut R) {
+        if self.len() < 2 {
+            return;
+        }
+        for _ in 0..n {
+            let (i, j) = (rng.gen_range(0..self.len()), rng.gen_range(0..self.len()));
+            self.swap(i, j);
+        }
+    }
+}
 // mutate:1 ends here
 
 // [[file:../spdkit.note::*test][test:1]]
@@ -102,4 +305,30 @@ fn test_binary() {
 
     assert_eq!(y.to_string(), "001");
 }
+
+#[test]
+fn test_real_vector() {
+    let mut x = RealVector::new(vec![-2.0, 0.5, 3.0]);
+    let bounds = Bounds::uniform(3, -1.0, 1.0);
+    bounds.clip(&mut x);
+    assert_eq!(x, RealVector::new(vec![-1.0, 0.5, 1.0]));
+
+    let mut rng = get_rng!();
+    let y = bounds.random(&mut *rng);
+    assert!(y.iter().all(|&v| (-1.0..=1.0).contains(&v)));
+}
+
+#[test]
+fn test_permutation() {
+    let x = Permutation::new(vec![2, 0, 1]);
+    assert_eq!(x.to_string(), "2 0 1");
+    assert_eq!(x.inverse(), Permutation::new(vec![1, 2, 0]));
+
+    let mut rng = get_rng!();
+    let mut y = Permutation::random(10, &mut *rng);
+    y.mutate(3, &mut *rng);
+    let mut sorted = y.to_vec();
+    sorted.sort();
+    assert_eq!(Permutation::new(sorted), Permutation::identity(10));
+}
 // test:1 ends here
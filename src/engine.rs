@@ -1,14 +1,20 @@
 // [[file:../spdkit.note::*imports][imports:1]]
+use std::collections::HashMap;
 use std::iter::FromIterator;
 
 use crate::common::*;
+use crate::diversity::*;
 use crate::encoding::*;
 use crate::fitness::*;
 use crate::gears::*;
+use crate::genealogy::Genealogy;
+use crate::hall_of_fame::HallOfFame;
 use crate::individual::*;
 use crate::operators::*;
 use crate::population::*;
 use crate::random::*;
+use crate::report::Report;
+use crate::restart::*;
 use crate::termination::*;
 // imports:1 ends here
 
@@ -27,18 +33,30 @@ where
         cur_population: &Population<G>,
         valuer: &mut Valuer<G, F, C>,
     ) -> Population<G>;
+
+    /// Report statistics of the last evolution step.
+    fn report(&self) -> Report {
+        Report::default()
+    }
 }
 // 109fedb5 ends here
 
 // [[file:../spdkit.note::*core][core:1]]
-pub struct EvolutionAlgorithm<G, B, S>
+pub struct EvolutionAlgorithm<G, B, S, L = NoLocalSearch>
 where
     G: Genome,
     B: Breed<G>,
     S: Survive<G>,
+    L: LocalSearch<G>,
 {
     breeder: B,
     survivor: S,
+    local_search: L,
+    learning_mode: LearningMode,
+    // None if never set
+    local_search_fraction: Option<f64>,
+    // the number of offspring improved by local search in last step
+    nimproved: usize,
     _g: PhantomData<G>,
 }
 
@@ -52,18 +70,64 @@ where
         Self {
             breeder,
             survivor,
+            local_search: NoLocalSearch,
+            learning_mode: LearningMode::Lamarckian,
+            local_search_fraction: None,
+            nimproved: 0,
+            _g: PhantomData,
+        }
+    }
+}
+
+impl<G, B, S, L> EvolutionAlgorithm<G, B, S, L>
+where
+    G: Genome,
+    B: Breed<G>,
+    S: Survive<G>,
+    L: LocalSearch<G>,
+{
+    /// Apply local search to offspring before evaluation (memetic algorithm).
+    /// By default, all offspring are improved in Lamarckian mode, unless the
+    /// fraction has been set by `local_search_fraction`.
+    pub fn with_local_search<M: LocalSearch<G>>(self, local_search: M) -> EvolutionAlgorithm<G, B, S, M> {
+        EvolutionAlgorithm {
+            breeder: self.breeder,
+            survivor: self.survivor,
+            local_search,
+            learning_mode: self.learning_mode,
+            local_search_fraction: Some(self.local_search_fraction.unwrap_or(1.0)),
+            nimproved: 0,
             _g: PhantomData,
         }
     }
+
+    /// Set how offspring inherit the result of local search.
+    pub fn learning_mode(mut self, mode: LearningMode) -> Self {
+        self.learning_mode = mode;
+        self
+    }
+
+    /// Set the fraction of offspring to be improved by local search.
+    pub fn local_search_fraction(mut self, f: f64) -> Self {
+        assert!((0.0..=1.0).contains(&f), "invalid fraction: {f}");
+        self.local_search_fraction = Some(f);
+        self
+    }
+
+    // The fraction of offspring to be improved by local search.
+    fn fraction(&self) -> f64 {
+        self.local_search_fraction.unwrap_or(0.0)
+    }
 }
 
-impl<G, C, B, S, F> Evolve<G, F, C> for EvolutionAlgorithm<G, B, S>
+impl<G, C, B, S, F, L> Evolve<G, F, C> for EvolutionAlgorithm<G, B, S, L>
 where
     G: Genome,
     C: EvaluateObjectiveValue<G>,
     B: Breed<G>,
     S: Survive<G>,
     F: EvaluateFitness<G>,
+    L: LocalSearch<G>,
 {
     fn next_generation(
         &mut self,
@@ -71,57 +135,153 @@ where
         valuer: &mut Valuer<G, F, C>,
     ) -> Population<G> {
         let mut rng = get_rng!();
-        evolve_one_step(
-            cur_population,
-            &mut self.breeder,
-            &mut self.survivor,
-            valuer,
-            &mut *rng,
-        )
+        self.evolve_one_step(cur_population, valuer, &mut *rng)
+    }
+
+    fn report(&self) -> Report {
+        let mut report = self.breeder.report();
+        report.merge(self.survivor.report());
+        if self.fraction() > 0.0 {
+            report.set("local_search/improved", self.nimproved);
+        }
+        report
     }
 }
 
-fn evolve_one_step<G, C, B, S, F, R>(
-    cur_population: &Population<G>,
-    breeder: &mut B,
-    survivor: &mut S,
-    valuer: &mut Valuer<G, F, C>,
-    rng: &mut R,
-) -> Population<G>
+impl<G, B, S, L> EvolutionAlgorithm<G, B, S, L>
 where
     G: Genome,
-    C: EvaluateObjectiveValue<G>,
     B: Breed<G>,
     S: Survive<G>,
-    F: EvaluateFitness<G>,
-    R: Rng + Sized,
+    L: LocalSearch<G>,
 {
-    // 1. create new individuals from parent population.
-    // 1.1 breed new genomes
-    let new_genomes = breeder.breed(cur_population.size_limit(), cur_population, rng);
-    // 1.2 create new individuals from genomes.
-    let mut new_indvs = valuer.create_individuals(new_genomes);
-    println!("bred {} new individuals", new_indvs.len());
-
-    // 2. create new population by supplanting bad performing individuals
-    // 2.1 combine all available individuals into one.
-    let old_indvs = cur_population.individuals();
-    new_indvs.extend_from_slice(old_indvs);
-
-    // 2.2 create a new population from combined new individuals
-    let nlimit = cur_population.size_limit();
-    let tmp_population = valuer.build_population(new_indvs).with_size_limit(nlimit);
-    let m = tmp_population.size();
-
-    // 2.3 remove low quality individuals
-    let survived_indvs = survivor.survive(tmp_population, rng);
-    let n = m - survived_indvs.len();
-    println!("removed {} bad individuals.", n);
-    let mut new_population = valuer
-        .build_population(survived_indvs)
-        .with_size_limit(nlimit);
-
-    new_population.to_owned()
+    /// Create individuals from newly bred genomes with their origins. A
+    /// fraction of them are improved by local search before evaluation. The
+    /// parents and the variation operator of each individual are recorded in
+    /// its lineage.
+    fn create_offspring<F, C, R>(
+        &mut self,
+        mut offspring: Vec<(G, Option<Origin>)>,
+        valuer: &Valuer<G, F, C>,
+        rng: &mut R,
+    ) -> Vec<Individual<G>>
+    where
+        C: EvaluateObjectiveValue<G>,
+        F: EvaluateFitness<G>,
+        R: Rng + Sized,
+    {
+        self.nimproved = 0;
+        let mut improved = vec![];
+        let fraction = self.fraction();
+        if fraction > 0.0 {
+            // select offspring for local search at random
+            offspring.shuffle(rng);
+            let n = (fraction * offspring.len() as f64).round() as usize;
+            let rest = offspring.split_off(n.min(offspring.len()));
+
+            let local_search = &self.local_search;
+            let mode = self.learning_mode;
+            let results: Vec<_> = offspring
+                .into_par_iter()
+                .map(|(g, origin)| match local_search.local_search(&g) {
+                    Some((better, score)) => {
+                        let origin = origin.map(|mut o| {
+                            o.operator.push_str("+local_search");
+                            o
+                        });
+                        match mode {
+                            LearningMode::Lamarckian => Ok((better, score, origin)),
+                            LearningMode::Baldwinian => Ok((g, score, origin)),
+                        }
+                    }
+                    None => Err((g, origin)),
+                })
+                .collect();
+
+            offspring = rest;
+            for x in results {
+                match x {
+                    Ok(x) => improved.push(x),
+                    Err(x) => offspring.push(x),
+                }
+            }
+            self.nimproved = improved.len();
+            info!("improved {} new genomes by local search", self.nimproved);
+        }
+
+        // individuals are created from unique genomes, keeping the first origin
+        let mut origins = HashMap::new();
+        let improved: Vec<_> = improved
+            .into_iter()
+            .map(|(g, score, origin)| {
+                origins.entry(g.clone()).or_insert(origin);
+                (g, score)
+            })
+            .collect();
+        let rest: Vec<_> = offspring
+            .into_iter()
+            .map(|(g, origin)| {
+                origins.entry(g.clone()).or_insert(origin);
+                g
+            })
+            .collect();
+        let mut indvs = valuer.create_evaluated_individuals(improved);
+        indvs.extend(valuer.create_individuals(rest));
+        for indv in indvs.iter_mut() {
+            if let Some(Some(origin)) = origins.get(indv.genome()) {
+                let lineage = indv.lineage_mut();
+                lineage.parents = origin.parent_ids.clone();
+                lineage.operator = origin.operator.clone();
+            }
+        }
+        indvs
+    }
+
+    fn evolve_one_step<C, F, R>(
+        &mut self,
+        cur_population: &Population<G>,
+        valuer: &mut Valuer<G, F, C>,
+        rng: &mut R,
+    ) -> Population<G>
+    where
+        C: EvaluateObjectiveValue<G>,
+        F: EvaluateFitness<G>,
+        R: Rng + Sized,
+    {
+        // 1. create new individuals from parent population.
+        // 1.1 breed new genomes
+        let new_genomes = self.breeder.breed(cur_population.size_limit(), cur_population, rng);
+        // pair genomes with their origins reported by breeder (if any)
+        let origins = self.breeder.origins();
+        let offspring: Vec<_> = if origins.len() == new_genomes.len() {
+            new_genomes.into_iter().zip(origins.iter().cloned().map(Some)).collect()
+        } else {
+            new_genomes.into_iter().map(|g| (g, None)).collect()
+        };
+        // 1.2 create new individuals from genomes, with optional local search.
+        let mut new_indvs = self.create_offspring(offspring, valuer, rng);
+        println!("bred {} new individuals", new_indvs.len());
+
+        // 2. create new population by supplanting bad performing individuals
+        // 2.1 combine all available individuals into one.
+        let old_indvs = cur_population.individuals();
+        new_indvs.extend_from_slice(old_indvs);
+
+        // 2.2 create a new population from combined new individuals
+        let nlimit = cur_population.size_limit();
+        let tmp_population = valuer.build_population(new_indvs).with_size_limit(nlimit);
+        let m = tmp_population.size();
+
+        // 2.3 remove low quality individuals
+        let survived_indvs = self.survivor.survive(tmp_population, rng);
+        let n = m - survived_indvs.len();
+        println!("removed {} bad individuals.", n);
+        let mut new_population = valuer
+            .build_population(survived_indvs)
+            .with_size_limit(nlimit);
+
+        new_population.to_owned()
+    }
 }
 // core:1 ends here
 
@@ -139,6 +299,18 @@ where
     algo: Option<E>,
     valuer: Option<Valuer<G, F, C>>,
     population: Option<Population<G>>,
+    hall_of_fame: Option<HallOfFame<G>>,
+
+    // recovery from stagnation
+    cataclysm: Option<(usize, Box<dyn Reseed<G>>)>,
+    max_restarts: usize,
+    immigrants: Option<(f64, Box<dyn Reseed<G>>)>,
+
+    // population diversity
+    diversity: Vec<Box<dyn Diversity<G>>>,
+    collapse: Option<DiversityCollapse>,
+
+    genealogy: Option<Genealogy>,
 }
 
 impl<G, E, F, C> Engine<G, E, F, C>
@@ -155,6 +327,16 @@ where
             algo: None,
             valuer: None,
             population: None,
+            hall_of_fame: None,
+
+            cataclysm: None,
+            max_restarts: 10,
+            immigrants: None,
+
+            diversity: vec![],
+            collapse: None,
+
+            genealogy: None,
         }
     }
 
@@ -182,6 +364,67 @@ where
         self
     }
 
+    /// Keep the best unique individuals ever seen in a hall of fame archive.
+    pub fn hall_of_fame(mut self, hof: HallOfFame<G>) -> Self {
+        self.hall_of_fame = Some(hof);
+        self
+    }
+
+    /// Take out the hall of fame archive, typically at the end of evolution.
+    pub fn take_hall_of_fame(&mut self) -> Option<HallOfFame<G>> {
+        self.hall_of_fame.take()
+    }
+
+    /// Record the genealogy of all individuals appeared in evolution.
+    pub fn track_genealogy(mut self) -> Self {
+        self.genealogy = Some(Genealogy::default());
+        self
+    }
+
+    /// Take out the recorded genealogy, typically at the end of evolution.
+    pub fn take_genealogy(&mut self) -> Option<Genealogy> {
+        self.genealogy.take()
+    }
+
+    /// Recover from stagnation by a cataclysmic restart instead of terminating
+    /// evolution: the best `nelite` members are kept, and the others are
+    /// replaced with genomes created by `reseed`.
+    pub fn cataclysm(mut self, nelite: usize, reseed: impl Reseed<G> + 'static) -> Self {
+        self.cataclysm = Some((nelite, Box::new(reseed)));
+        self
+    }
+
+    /// The max number of cataclysmic restarts. Evolution terminates on
+    /// stagnation when exceeded. The default is 10.
+    pub fn max_restarts(mut self, n: usize) -> Self {
+        self.max_restarts = n;
+        self
+    }
+
+    /// Replace a `fraction` of the worst members with random immigrants
+    /// created by `reseed` in every generation.
+    pub fn random_immigrants(mut self, fraction: f64, reseed: impl Reseed<G> + 'static) -> Self {
+        assert!((0.0..=1.0).contains(&fraction), "invalid fraction: {fraction}");
+        self.immigrants = Some((fraction, Box::new(reseed)));
+        self
+    }
+
+    /// Report diversity `measure` of population in each generation as
+    /// "diversity/<name>". The coefficient of variation of objective values is
+    /// always reported as "diversity/objective_cv".
+    pub fn diversity(mut self, measure: impl Diversity<G> + 'static) -> Self {
+        self.diversity.push(Box::new(measure));
+        self
+    }
+
+    /// Treat population as stagnant when diversity measure `name` drops below
+    /// `threshold`, which leads to a cataclysmic restart if enabled, or
+    /// termination otherwise.
+    pub fn diversity_threshold(mut self, name: &str, threshold: f64) -> Self {
+        self.collapse = Some(DiversityCollapse::new(&format!("diversity/{name}"), threshold));
+        self
+    }
+
     /// Evolves one step forward from seeds.
     ///
     /// # Parameters
@@ -208,6 +451,7 @@ where
 
         // enter main loop
         let mut ig = 0;
+        let mut nrestarts = 0;
         std::iter::from_fn(move || {
             if ig == 0 {
                 println!("initial population:");
@@ -218,16 +462,61 @@ where
                 let new_population = algo.next_generation(&population, &mut valuer);
 
                 population = new_population;
+                if let Some((fraction, reseed)) = self.immigrants.as_mut() {
+                    let n = (*fraction * population.size_limit() as f64).round() as usize;
+                    let nkeep = population.size().saturating_sub(n);
+                    let mut rng = get_rng!();
+                    population = reseed_population(&population, nkeep, reseed.as_mut(), &mut valuer, &mut *rng);
+                    info!("injected {n} random immigrants.");
+                }
+            }
+
+            mark_birth(&mut population, ig);
+            valuer.update_fitness(&population);
+            if let Some(genealogy) = self.genealogy.as_mut() {
+                genealogy.update(&population);
             }
 
+            let hall_of_fame = if let Some(hof) = self.hall_of_fame.as_mut() {
+                hof.update(&population, &valuer);
+                hof.individuals().to_vec()
+            } else {
+                vec![]
+            };
+
+            let mut report = if ig == 0 { Report::default() } else { algo.report() };
+            if self.cataclysm.is_some() {
+                report.set("engine/restarts", nrestarts);
+            }
+            report.set("diversity/objective_cv", population.objective_cv());
+            for measure in self.diversity.iter() {
+                report.set(format!("diversity/{}", measure.name()), measure.measure(&population));
+            }
             let g = Generation {
                 index: ig,
                 population: population.clone(),
+                report,
+                hall_of_fame,
             };
             ig += 1;
 
             // avoid infinite loop using a reliable termination criterion.
-            if termination.meets(&g) {
+            let collapsed = self.collapse.as_mut().is_some_and(|c| c.meets(&g));
+            if collapsed {
+                warn!("Population diversity collapsed.");
+            }
+            if collapsed || termination.meets(&g) {
+                if let Some((nelite, reseed)) = self.cataclysm.as_mut() {
+                    if nrestarts < self.max_restarts {
+                        nrestarts += 1;
+                        warn!("Stagnation detected. Cataclysmic restart {nrestarts} ...");
+                        let mut rng = get_rng!();
+                        population = reseed_population(&population, *nelite, reseed.as_mut(), &mut valuer, &mut *rng);
+                        mark_birth(&mut population, ig);
+                        termination = RunningMean::new(self.nlast);
+                        return Some(Ok(g));
+                    }
+                }
                 error!("Terminated for stagnation!");
                 error!(
                     "Simulation has evolved for {} generations without changes.",
@@ -241,6 +530,16 @@ where
         })
     }
 }
+
+// Set birth generation of newborn individuals in `population`.
+fn mark_birth<G: Genome>(population: &mut Population<G>, ig: usize) {
+    for indv in population.individuals_mut() {
+        let lineage = indv.lineage_mut();
+        if lineage.birth.is_none() {
+            lineage.birth = Some(ig);
+        }
+    }
+}
 // pub:1 ends here
 
 // [[file:../spdkit.note::*test][test:1]]
@@ -272,14 +571,255 @@ mod test {
         let algo = EvolutionAlgorithm::new(breeder, survivor);
 
         let seeds = build_initial_genomes(10);
-        for g in Engine::create()
+        let mut engine = Engine::create()
             .valuer(valuer)
             .algorithm(algo)
-            .evolve(&seeds)
-            .take(10)
-        {
+            .hall_of_fame(HallOfFame::new(3));
+        for g in engine.evolve(&seeds).take(10) {
             let generation = g?;
             generation.summary();
+            assert!(generation.hall_of_fame.len() <= 3);
+        }
+        let hof = engine.take_hall_of_fame().unwrap();
+        assert_eq!(hof.individuals().len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_engine_restart() -> Result<()> {
+        let valuer = Valuer::new()
+            .with_fitness(fitness::Maximize)
+            .with_creator(OneMax);
+        let breeder = crate::gears::GeneticBreeder::new()
+            .with_crossover(OnePointCrossOver)
+            .with_selector(RouletteWheelSelection::new(2));
+        let algo = EvolutionAlgorithm::new(breeder, Survivor::default());
+
+        let seeds = build_initial_genomes(10);
+        let mut engine = Engine::create()
+            .valuer(valuer)
+            .algorithm(algo)
+            .termination_nlast(2)
+            .cataclysm(2, HeavyMutation::new(5))
+            .max_restarts(2)
+            .random_immigrants(0.2, |rng: &mut dyn RngCore| random_binary_with(11, rng));
+        let mut nrestarts = 0;
+        let mut best = 0.0;
+        for g in engine.evolve(&seeds).take(1000) {
+            let generation = g?;
+            assert!(generation.population.size() <= 10);
+            let n = generation.report.get("engine/restarts").and_then(|x| x.as_count()).unwrap();
+            let m = generation.population.best_member().unwrap().objective_value();
+            // the elites survive cataclysmic restart
+            if n > nrestarts {
+                assert!(m >= best);
+            }
+            nrestarts = n;
+            best = m;
+        }
+        // stagnation of OneMax is inevitable, restarting until exceeding the limit
+        assert_eq!(nrestarts, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_engine_diversity() -> Result<()> {
+        use crate::distance::Hamming;
+
+        let valuer = Valuer::new()
+            .with_fitness(fitness::Maximize)
+            .with_creator(OneMax);
+        let breeder = crate::gears::GeneticBreeder::new()
+            .with_crossover(OnePointCrossOver)
+            .with_selector(RouletteWheelSelection::new(2));
+        let algo = EvolutionAlgorithm::new(breeder, Survivor::default());
+
+        let seeds = build_initial_genomes(10);
+        let mut engine = Engine::create()
+            .valuer(valuer)
+            .algorithm(algo)
+            .diversity(MeanDistance(Hamming))
+            .diversity(LocusEntropy);
+        let mut collapse = DiversityCollapse::new("diversity/locus_entropy", 10.0);
+        let mut healthy = DiversityCollapse::new("diversity/locus_entropy", 0.0);
+        for g in engine.evolve(&seeds).take(3) {
+            let generation = g?;
+            let report = &generation.report;
+            assert!(report.get("diversity/objective_cv").is_some());
+            assert!(report.get("diversity/mean_distance").is_some());
+            let entropy = report.get("diversity/locus_entropy").and_then(|x| x.as_number()).unwrap();
+            assert!(entropy <= 1.0);
+            // locus entropy is at most 1.0
+            assert!(collapse.meets(&generation));
+            assert!(!healthy.meets(&generation));
+        }
+
+        // collapse of diversity leads to restart in every generation
+        let valuer = Valuer::new()
+            .with_fitness(fitness::Maximize)
+            .with_creator(OneMax);
+        let breeder = crate::gears::GeneticBreeder::new()
+            .with_crossover(OnePointCrossOver)
+            .with_selector(RouletteWheelSelection::new(2));
+        let algo = EvolutionAlgorithm::new(breeder, Survivor::default());
+        let mut engine = Engine::create()
+            .valuer(valuer)
+            .algorithm(algo)
+            .diversity(LocusEntropy)
+            .diversity_threshold("locus_entropy", 10.0)
+            .cataclysm(2, HeavyMutation::new(5))
+            .max_restarts(3);
+        for (i, g) in engine.evolve(&seeds).take(10).enumerate() {
+            let report = g?.report;
+            assert_eq!(report.get("engine/restarts").and_then(|x| x.as_count()), Some(i));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_engine_genealogy() -> Result<()> {
+        let valuer = Valuer::new()
+            .with_fitness(fitness::Maximize)
+            .with_creator(OneMax);
+        let breeder = crate::gears::GeneticBreeder::new()
+            .with_crossover(OnePointCrossOver)
+            .with_selector(RouletteWheelSelection::new(2));
+        let algo = EvolutionAlgorithm::new(breeder, Survivor::default());
+
+        let seeds = build_initial_genomes(10);
+        let mut engine = Engine::create().valuer(valuer).algorithm(algo).track_genealogy();
+        for g in engine.evolve(&seeds).take(5) {
+            let generation = g?;
+            for indv in generation.population.individuals() {
+                assert!(indv.lineage().birth.is_some_and(|x| x <= generation.index));
+            }
+        }
+        let genealogy = engine.take_genealogy().unwrap();
+        assert!(genealogy.len() >= 10);
+        // children bred by crossover link to recorded parents
+        let child = genealogy.records().find(|r| !r.lineage.parents.is_empty()).unwrap();
+        assert!(child.lineage.operator.starts_with("crossover"));
+        assert!(child.lineage.birth.unwrap() > 0);
+        let ancestors = genealogy.ancestors(child.lineage.id);
+        assert!(child.lineage.parents.iter().all(|p| ancestors.contains(p)));
+        assert!(genealogy.to_dot().contains("->"));
+
+        Ok(())
+    }
+
+    // flip the first zero bit
+    #[derive(Clone)]
+    struct FlipFirstZero;
+
+    impl LocalSearch<Binary> for FlipFirstZero {
+        fn local_search(&self, genome: &Binary) -> Option<(Binary, f64)> {
+            let i = genome.iter().position(|&b| !b)?;
+            let mut g = genome.clone();
+            g.flip(vec![i]);
+            let score = OneMax.evaluate(&g);
+            Some((g, score))
+        }
+    }
+
+    #[test]
+    fn test_memetic() -> Result<()> {
+        let valuer = Valuer::new()
+            .with_fitness(fitness::Maximize)
+            .with_creator(OneMax);
+        let breeder = crate::gears::GeneticBreeder::new()
+            .with_crossover(OnePointCrossOver)
+            .with_selector(RouletteWheelSelection::new(2));
+        let algo = EvolutionAlgorithm::new(breeder, Survivor::default())
+            .with_local_search(FlipFirstZero)
+            .learning_mode(LearningMode::Baldwinian)
+            .local_search_fraction(0.5);
+
+        let seeds = build_initial_genomes(10);
+        let mut engine = Engine::create().valuer(valuer).algorithm(algo);
+        for g in engine.evolve(&seeds).skip(1).take(3) {
+            let generation = g?;
+            let n = generation.report.get("local_search/improved").and_then(|x| x.as_count());
+            assert!(n.is_some());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_learning_mode() {
+        let valuer = Valuer::new()
+            .with_fitness(fitness::Maximize)
+            .with_creator(OneMax);
+        let breeder = crate::gears::GeneticBreeder::new()
+            .with_crossover(OnePointCrossOver)
+            .with_selector(RouletteWheelSelection::new(2));
+        let mut rng = get_rng!();
+
+        // the fraction set before local search is kept
+        let algo = EvolutionAlgorithm::new(breeder.clone(), Survivor::default())
+            .local_search_fraction(0.2)
+            .with_local_search(FlipFirstZero);
+        assert_eq!(algo.fraction(), 0.2);
+        let algo = EvolutionAlgorithm::new(breeder.clone(), Survivor::default()).with_local_search(FlipFirstZero);
+        assert_eq!(algo.fraction(), 1.0);
+
+        // the original genome is kept with the improved score
+        let mut algo = EvolutionAlgorithm::new(breeder.clone(), Survivor::default())
+            .with_local_search(FlipFirstZero)
+            .learning_mode(LearningMode::Baldwinian);
+        let indvs = algo.create_offspring(vec![(Binary::from_str("0011"), None)], &valuer, &mut *rng);
+        assert_eq!(indvs[0].genome().to_string(), "0011");
+        assert_eq!(indvs[0].objective_value(), 3.0);
+        assert!(indvs[0].lineage().parents.is_empty());
+
+        // both genome and score are improved
+        let mut algo = EvolutionAlgorithm::new(breeder, Survivor::default())
+            .with_local_search(FlipFirstZero)
+            .learning_mode(LearningMode::Lamarckian);
+        // the origin of the improved genome is kept
+        let origin = Origin {
+            operator: "crossover".into(),
+            parent_ids: vec![7, 8],
+        };
+        let offspring = vec![(Binary::from_str("0011"), Some(origin))];
+        let indvs = algo.create_offspring(offspring, &valuer, &mut *rng);
+        assert_eq!(indvs[0].genome().to_string(), "1011");
+        assert_eq!(indvs[0].objective_value(), 3.0);
+        assert_eq!(indvs[0].lineage().parents, vec![7, 8]);
+        assert_eq!(indvs[0].lineage().operator, "crossover+local_search");
+    }
+
+    #[test]
+    fn test_memetic_genealogy() -> Result<()> {
+        let valuer = Valuer::new()
+            .with_fitness(fitness::Maximize)
+            .with_creator(OneMax);
+        let breeder = crate::gears::GeneticBreeder::new()
+            .with_crossover(OnePointCrossOver)
+            .with_selector(RouletteWheelSelection::new(2));
+        let algo = EvolutionAlgorithm::new(breeder, Survivor::default())
+            .with_local_search(FlipFirstZero)
+            .learning_mode(LearningMode::Lamarckian)
+            .local_search_fraction(0.5);
+
+        let seeds = build_initial_genomes(10);
+        let mut engine = Engine::create().valuer(valuer).algorithm(algo).track_genealogy();
+        for g in engine.evolve(&seeds).take(5) {
+            let _ = g?;
+        }
+        let genealogy = engine.take_genealogy().unwrap();
+        // improved children still link to their parents
+        let improved: Vec<_> = genealogy
+            .records()
+            .filter(|r| r.lineage.operator.ends_with("+local_search"))
+            .collect();
+        assert!(!improved.is_empty());
+        for child in improved {
+            assert!(child.lineage.operator.starts_with("crossover"));
+            assert_eq!(child.lineage.parents.len(), 2);
         }
 
         Ok(())
@@ -293,6 +833,10 @@ mod test {
 
     fn random_binary(length: usize) -> Binary {
         let mut rng = get_rng!();
+        random_binary_with(length, &mut *rng)
+    }
+
+    fn random_binary_with(length: usize, rng: &mut dyn RngCore) -> Binary {
         let list: Vec<_> = (0..length).map(|_| rng.gen()).collect();
         Binary::new(list)
     }
@@ -2,11 +2,12 @@
 use crate::common::*;
 use crate::encoding::Binary;
 use crate::individual::*;
+use crate::population::Population;
 // imports:1 ends here
 
 // [[file:../spdkit.note::*base][base:1]]
 // avoid zero fitness value
-const EPSILON: f64 = 10e-8;
+pub(crate) const EPSILON: f64 = 10e-8;
 
 /// Evaluate the fitness of individual in population based on objective_value of
 /// individual.
@@ -21,6 +22,11 @@ where
     G: Genome,
 {
     fn evaluate(&mut self, indvs: &[Individual<G>]) -> Vec<f64>;
+
+    /// Update internal state (if any) from the population of a new
+    /// generation, e.g. the archive of novelty search. This is called once
+    /// per generation by `Engine`. The default implementation does nothing.
+    fn update(&mut self, _population: &Population<G>) {}
 }
 
 /// For Maximizing individual objective value. The larger of individual objective value, the
@@ -68,36 +74,35 @@ where
 
 // [[file:../spdkit.note::*minimize energy][minimize energy:1]]
 use crate::annealing::Annealer;
+use crate::units::{EnergyUnit, Temperature};
 
 /// Minimize energy with Boltzmann distribution. The lower of the energy, the
 /// better of an individual.
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MinimizeEnergy {
-    conversion: f64,
+    #[serde(default)]
+    unit: EnergyUnit,
     annealer: Annealer,
-    temperature: f64,
+    temperature: Temperature,
 }
 
 impl MinimizeEnergy {
-    pub fn new(temperature: f64) -> Self {
-        assert!(temperature.is_sign_positive(), "temperature cannot be negative!");
+    /// Construct with `temperature` in Kelvin or as a `Temperature`. The
+    /// objective value of individual is assumed in eV by default.
+    pub fn new(temperature: impl Into<Temperature>) -> Self {
+        let temperature = temperature.into();
 
         Self {
-            conversion: 96.0,
+            unit: EnergyUnit::default(),
             // FIXME: adhoc hacking
-            annealer: Annealer::new(temperature * 10.0, temperature).cooling_rate(0.99),
+            annealer: Annealer::new(temperature.to_kelvin() * 10.0, temperature).cooling_rate(0.99),
             temperature,
         }
     }
 
-    pub fn energy_unit(mut self, u: &str) -> Self {
-        match u {
-            "eV" => self.conversion = 96.0,
-            "au" => self.conversion = 2625.5,
-            "kcal" => self.conversion = 4.184,
-            "kJ" => self.conversion = 1.0,
-            _ => panic!("unkonw unit: {}", u),
-        }
+    /// Set energy unit of individual objective value.
+    pub fn energy_unit(mut self, u: EnergyUnit) -> Self {
+        self.unit = u;
         self
     }
 }
@@ -113,15 +118,12 @@ where
         } else {
             self.temperature
         };
-        println!("annealing temperature: {}", temperature);
+        debug!("annealing temperature: {}", temperature);
 
         if let Some(score_ref) = indvs.iter().map(|indv| indv.objective_value()).fmin() {
             indvs
                 .iter()
-                .map(|x| {
-                    let value = self.conversion * (score_ref - x.objective_value());
-                    (value / (temperature * 0.0083145)).exp()
-                })
+                .map(|x| temperature.boltzmann_factor(x.objective_value() - score_ref, self.unit))
                 .collect()
         } else {
             warn!("empty individual list!");
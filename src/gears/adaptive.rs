@@ -0,0 +1,376 @@
+// [[file:../../spdkit.note::*imports][imports:1]]
+use std::collections::HashMap;
+
+use crate::common::*;
+use crate::individual::*;
+use crate::operators::*;
+use crate::random::*;
+use crate::report::Report;
+
+use super::portfolio::Entry;
+use super::*;
+// imports:1 ends here
+
+// [[file:../../spdkit.note::5c0d7e42][5c0d7e42]]
+/// Strategies for adaptive operator selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OperatorSelection {
+    /// Select operators with probabilities proportional to their qualities.
+    #[default]
+    ProbabilityMatching,
+    /// Increase the probability of the best operator, and decrease the others.
+    AdaptivePursuit,
+    /// Select operator using the upper confidence bound of multi-armed bandit.
+    Ucb,
+}
+
+/// A breeder selecting its variation operators adaptively, based on the
+/// fitness improvement of children over their parents.
+///
+/// The credit of children is assigned at next breeding, using fitness values
+/// in the new parent population. A child not surviving gets no credit, and a
+/// parent not surviving is assumed to have the worst fitness in population.
+///
+/// # Reference
+///
+/// * Thierens 2005, An Adaptive Pursuit Strategy for Allocating Operator Probabilities
+/// * Fialho et al. 2010, Analyzing bandit-based adaptive operator selection mechanisms
+///
+#[derive(Clone)]
+pub struct AdaptiveBreeder<G: Genome> {
+    entries: Vec<Entry<G>>,
+    strategy: OperatorSelection,
+
+    p_min: f64,
+    alpha: f64,
+    beta: f64,
+    exploration: f64,
+
+    qualities: Vec<f64>,
+    probabilities: Vec<f64>,
+    // the number of applications of each operator in total
+    napplied: Vec<usize>,
+    // children bred in last breeding kept for credit assignment, their
+    // origins, and the index of operator breeding each child
+    children: Vec<G>,
+    origins: Vec<Origin>,
+    chosen: Vec<usize>,
+}
+
+impl<G: Genome> Default for AdaptiveBreeder<G> {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+            strategy: OperatorSelection::default(),
+            p_min: 0.05,
+            alpha: 0.3,
+            beta: 0.8,
+            exploration: 1.0,
+            qualities: vec![],
+            probabilities: vec![],
+            napplied: vec![],
+            children: vec![],
+            origins: vec![],
+            chosen: vec![],
+        }
+    }
+}
+
+impl<G: Genome> AdaptiveBreeder<G> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add variation `operator` named as `name`. Parents are selected from
+    /// population using `selector`.
+    pub fn with_operator<C, S>(mut self, name: &str, operator: C, selector: S) -> Self
+    where
+        C: VariationOperator<G> + 'static,
+        S: SelectionOperator + 'static,
+    {
+        assert!(
+            self.entries.iter().all(|x| x.name != name),
+            "duplicated operator name: {name}"
+        );
+        self.entries.push(Entry::new(name, 1.0, operator, selector));
+        let k = self.entries.len() as f64;
+        self.qualities = vec![1.0; self.entries.len()];
+        self.probabilities = vec![1.0 / k; self.entries.len()];
+        self.napplied = vec![0; self.entries.len()];
+        self
+    }
+
+    /// Set the strategy for operator selection.
+    pub fn strategy(mut self, strategy: OperatorSelection) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Set the minimum probability of each operator. The default is 0.05.
+    pub fn min_probability(mut self, p: f64) -> Self {
+        assert!((0.0..1.0).contains(&p), "invalid probability: {p}");
+        self.p_min = p;
+        self
+    }
+
+    /// Set the adaptation rate for updating operator qualities. The default
+    /// is 0.3.
+    pub fn adaptation_rate(mut self, alpha: f64) -> Self {
+        assert!(alpha > 0.0 && alpha <= 1.0, "invalid adaptation rate: {alpha}");
+        self.alpha = alpha;
+        self
+    }
+
+    /// Set the learning rate of adaptive pursuit. The default is 0.8.
+    pub fn learning_rate(mut self, beta: f64) -> Self {
+        assert!(beta > 0.0 && beta <= 1.0, "invalid learning rate: {beta}");
+        self.beta = beta;
+        self
+    }
+
+    /// Set the scaling factor of exploration term in UCB. The default is 1.0.
+    pub fn exploration(mut self, c: f64) -> Self {
+        assert!(c.is_sign_positive(), "invalid exploration factor: {c}");
+        self.exploration = c;
+        self
+    }
+
+    /// Return current probabilities of operators in the order of addition.
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+
+    /// Return current qualities of operators in the order of addition.
+    pub fn qualities(&self) -> &[f64] {
+        &self.qualities
+    }
+}
+
+impl<G: Genome> AdaptiveBreeder<G> {
+    // Return mean rewards of operators applied in last breeding, normalized by
+    // the largest one.
+    fn rewards(&self, population: &Population<G>) -> Vec<Option<f64>> {
+        let fitness: HashMap<&G, f64> = population
+            .members()
+            .map(|m| (m.individual.genome(), m.fitness_value()))
+            .collect();
+        let parent_fitness: HashMap<usize, f64> = population
+            .members()
+            .map(|m| (m.individual.id(), m.fitness_value()))
+            .collect();
+        let fmin = fitness.values().fmin().unwrap_or(0.0);
+
+        let k = self.entries.len();
+        let mut total = vec![0.0; k];
+        let mut count = vec![0; k];
+        for ((g, origin), i) in self.children.iter().zip(&self.origins).zip(&self.chosen) {
+            let improvement = match fitness.get(g) {
+                Some(fc) => {
+                    let fp = origin
+                        .parent_ids
+                        .iter()
+                        .map(|p| parent_fitness.get(p).copied().unwrap_or(fmin))
+                        .fmax()
+                        .unwrap_or(fmin);
+                    (fc - fp).max(0.0)
+                }
+                None => 0.0,
+            };
+            total[*i] += improvement;
+            count[*i] += 1;
+        }
+
+        let rewards: Vec<_> = total
+            .into_iter()
+            .zip(count)
+            .map(|(t, n)| if n > 0 { Some(t / n as f64) } else { None })
+            .collect();
+        let rmax = rewards.iter().flatten().fmax().unwrap_or(0.0);
+        rewards
+            .into_iter()
+            .map(|r| r.map(|x| if rmax > 0.0 { x / rmax } else { 0.0 }))
+            .collect()
+    }
+
+    // Assign credit to operators applied in last breeding.
+    fn update(&mut self, population: &Population<G>) {
+        if self.origins.is_empty() {
+            return;
+        }
+        let rewards = self.rewards(population);
+        for (q, r) in self.qualities.iter_mut().zip(rewards) {
+            if let Some(r) = r {
+                *q += self.alpha * (r - *q);
+            }
+        }
+
+        let k = self.entries.len() as f64;
+        let p_min = self.p_min.min(1.0 / k);
+        match self.strategy {
+            OperatorSelection::ProbabilityMatching => {
+                let qsum: f64 = self.qualities.iter().sum();
+                for (p, q) in self.probabilities.iter_mut().zip(&self.qualities) {
+                    *p = if qsum > 0.0 {
+                        p_min + (1.0 - k * p_min) * q / qsum
+                    } else {
+                        1.0 / k
+                    };
+                }
+            }
+            OperatorSelection::AdaptivePursuit => {
+                let p_max = 1.0 - (k - 1.0) * p_min;
+                let (ibest, _) = self.qualities.iter().imax().expect("no operator");
+                for (i, p) in self.probabilities.iter_mut().enumerate() {
+                    let target = if i == ibest { p_max } else { p_min };
+                    *p += self.beta * (target - *p);
+                }
+            }
+            // probabilities are updated during breeding
+            OperatorSelection::Ucb => {}
+        }
+    }
+
+    // Choose the index of operator for next variation.
+    fn choose<R: Rng + Sized>(&self, rng: &mut R) -> usize {
+        match self.strategy {
+            OperatorSelection::Ucb => {
+                if let Some(i) = self.napplied.iter().position(|&n| n == 0) {
+                    return i;
+                }
+                let ntotal: usize = self.napplied.iter().sum();
+                let ln_n = (ntotal as f64).ln();
+                let (i, _) = self
+                    .qualities
+                    .iter()
+                    .zip(&self.napplied)
+                    .map(|(q, &n)| q + self.exploration * (2.0 * ln_n / n as f64).sqrt())
+                    .imax()
+                    .expect("no operator");
+                i
+            }
+            _ => {
+                let indices: Vec<_> = (0..self.entries.len()).collect();
+                *indices
+                    .choose_weighted(rng, |&i| self.probabilities[i])
+                    .unwrap_or_else(|e| panic!("Weighted selection failed: {:?}", e))
+            }
+        }
+    }
+}
+
+impl<G: Genome> Breed<G> for AdaptiveBreeder<G> {
+    /// Breed `m` new genomes from parent population.
+    fn breed<R: Rng + Sized>(&mut self, m: usize, population: &Population<G>, rng: &mut R) -> Vec<G> {
+        assert!(!self.entries.is_empty(), "breeder has no operator.");
+
+        self.update(population);
+        self.children.clear();
+        self.origins.clear();
+        self.chosen.clear();
+        while self.children.len() < m {
+            let i = self.choose(rng);
+            self.napplied[i] += 1;
+            let entry = &self.entries[i];
+            let (new_genomes, origin) = entry.vary(population, rng);
+            for g in new_genomes {
+                self.children.push(g);
+                self.origins.push(origin.clone());
+                self.chosen.push(i);
+            }
+        }
+        self.children.truncate(m);
+        self.origins.truncate(m);
+        self.chosen.truncate(m);
+
+        // for UCB: the fraction of children bred by each operator
+        if self.strategy == OperatorSelection::Ucb {
+            let n = self.origins.len() as f64;
+            for (k, p) in self.probabilities.iter_mut().enumerate() {
+                *p = self.chosen.iter().filter(|&&i| i == k).count() as f64 / n;
+            }
+        }
+
+        self.children.clone()
+    }
+
+    fn origins(&self) -> &[Origin] {
+        &self.origins
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        for (k, entry) in self.entries.iter().enumerate() {
+            let n = self.chosen.iter().filter(|&&i| i == k).count();
+            report.set(format!("operator/{}", entry.name), n);
+            report.set(format!("operator_probability/{}", entry.name), self.probabilities[k]);
+        }
+        report
+    }
+}
+// 5c0d7e42 ends here
+
+// [[file:../../spdkit.note::*test][test:1]]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::encoding::Binary;
+    use crate::fitness::Maximize;
+    use crate::operators::selection::RandomSelection;
+    use vecfx::approx::assert_relative_eq;
+
+    // flip the first bit different from `self.0` in parent genome
+    #[derive(Debug, Clone)]
+    struct FlipFirst(bool);
+
+    impl VariationOperator<Binary> for FlipFirst {
+        fn breed_from<R: Rng + Sized>(&self, parents: &[Member<Binary>], _rng: &mut R) -> Vec<Binary> {
+            let mut g = parents[0].genome().clone();
+            if let Some(i) = g.iter().position(|&b| b != self.0) {
+                g.flip(vec![i]);
+            }
+            vec![g]
+        }
+    }
+
+    #[test]
+    fn test_adaptive_breeder() {
+        for strategy in [
+            OperatorSelection::ProbabilityMatching,
+            OperatorSelection::AdaptivePursuit,
+            OperatorSelection::Ucb,
+        ] {
+            // the bad operator is added first, so that ties in qualities
+            // cannot favor the good one. Every child of the good operator
+            // improves on its parent, and no child of the bad one does. A
+            // large minimum probability makes sure the bad one gets tried.
+            let mut breeder = AdaptiveBreeder::new()
+                .with_operator("bad", FlipFirst(false), RandomSelection::new(1))
+                .with_operator("good", FlipFirst(true), RandomSelection::new(1))
+                .min_probability(0.2)
+                .strategy(strategy);
+
+            let genomes: Vec<_> = ["11100000000000000000000000000000", "00000000000000000000000000000111"]
+                .iter()
+                .map(|x| Binary::from_str(x))
+                .collect();
+            let mut indvs = OneMax.create(genomes);
+            let mut rng = get_rng!();
+            for _ in 0..20 {
+                let population = Population::build(indvs.clone(), &mut Maximize);
+                let children = breeder.breed(8, &population, &mut *rng);
+                indvs.extend(OneMax.create(children));
+            }
+            // both operators have been applied and credited
+            assert!(breeder.napplied.iter().all(|&n| n > 0), "{strategy:?}");
+            let q = breeder.qualities();
+            assert!(q[1] > q[0], "{strategy:?}: {q:?}");
+            let p = breeder.probabilities();
+            assert!(p[1] > p[0], "{strategy:?}: {p:?}");
+            assert_relative_eq!(p.iter().sum::<f64>(), 1.0, epsilon = 1e-6);
+
+            let report = breeder.report();
+            assert!(report.get("operator_probability/good").is_some());
+        }
+    }
+}
+// test:1 ends here
@@ -1,31 +1,61 @@
 // [[file:../../spdkit.note::*imports][imports:1]]
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use crate::common::*;
+use crate::distance::GenomeDistance;
 use crate::encoding::*;
 use crate::individual::*;
+use crate::operators::repair::NoRepair;
 use crate::operators::*;
 use crate::population::*;
 use crate::random::*;
+use crate::report::Report;
 
 use super::*;
 // imports:1 ends here
 
+/// Mating restrictions on the pair of parents for crossover, based on the
+/// distance between their genomes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Mating {
+    /// Refuse pairs of parents closer than `threshold` (incest prevention).
+    IncestPrevention { threshold: f64 },
+    /// Mate the first parent with the most similar one among `ncandidates`
+    /// selected partners (positive assortative mating).
+    Assortative { ncandidates: usize },
+    /// Mate the first parent with the most dissimilar one among
+    /// `ncandidates` selected partners (negative assortative mating).
+    Disassortative { ncandidates: usize },
+}
+
 /// A breeder for genetic algorithm featuring a combined use of crossover and
-/// mutation operators.
+/// mutation operators. Every child is repaired after variation, and rejected
+/// if it cannot be repaired.
 #[derive(Clone)]
-pub struct GeneticBreeder<C, S, G>
+pub struct GeneticBreeder<C, S, G, P = NoRepair>
 where
     C: VariationOperator<G>,
     S: SelectionOperator,
     G: Genome + Mutate,
+    P: Repair<G>,
 {
     cx_prob: f64,
     mut_prob: f64,
 
     crossover: Option<C>,
     selector: Option<S>,
+    repair: P,
+    mating: Option<(Arc<dyn GenomeDistance<G>>, Mating)>,
     _g: PhantomData<G>,
+
+    // the number of rejected children in last breeding and in total
+    nrejected: usize,
+    nrejected_total: usize,
+    // the number of pairs refused by mating restriction in last breeding
+    nrefused: usize,
+    // origins of children bred in last breeding
+    origins: Vec<Origin>,
 }
 
 impl<C, S, G> GeneticBreeder<C, S, G>
@@ -40,10 +70,24 @@ where
             mut_prob: 0.1,
             crossover: None,
             selector: None,
+            repair: NoRepair,
+            mating: None,
             _g: PhantomData,
+            nrejected: 0,
+            nrejected_total: 0,
+            nrefused: 0,
+            origins: vec![],
         }
     }
+}
 
+impl<C, S, G, P> GeneticBreeder<C, S, G, P>
+where
+    C: VariationOperator<G>,
+    S: SelectionOperator,
+    G: Genome + Mutate,
+    P: Repair<G>,
+{
     pub fn with_crossover(mut self, c: C) -> Self {
         self.crossover = Some(c);
         self
@@ -54,6 +98,38 @@ where
         self
     }
 
+    /// Set repair operator applied to every child after variation.
+    pub fn with_repair<Q: Repair<G>>(self, repair: Q) -> GeneticBreeder<C, S, G, Q> {
+        GeneticBreeder {
+            cx_prob: self.cx_prob,
+            mut_prob: self.mut_prob,
+            crossover: self.crossover,
+            selector: self.selector,
+            repair,
+            mating: self.mating,
+            _g: PhantomData,
+            nrejected: 0,
+            nrejected_total: 0,
+            nrefused: 0,
+            origins: vec![],
+        }
+    }
+
+    /// Restrict mating of parents using genome distance `metric`, e.g.
+    /// `InertiaDisparity` for molecules.
+    pub fn with_mating_restriction(mut self, metric: impl GenomeDistance<G> + 'static, mating: Mating) -> Self {
+        match mating {
+            Mating::IncestPrevention { threshold } => {
+                assert!(threshold.is_sign_positive(), "invalid threshold: {threshold}")
+            }
+            Mating::Assortative { ncandidates } | Mating::Disassortative { ncandidates } => {
+                assert!(ncandidates > 0, "invalid number of candidates: {ncandidates}")
+            }
+        }
+        self.mating = Some((Arc::new(metric), mating));
+        self
+    }
+
     pub fn crossover_probability(mut self, p: f64) -> Self {
         assert!(p.is_sign_positive());
 
@@ -67,13 +143,78 @@ where
 
         self
     }
+
+    /// Return the number of children rejected by repair operator in last
+    /// breeding.
+    pub fn nrejected(&self) -> usize {
+        self.nrejected
+    }
+
+    /// Return the number of parent pairs refused by mating restriction in
+    /// last breeding.
+    pub fn nrefused(&self) -> usize {
+        self.nrefused
+    }
+}
+
+impl<C, S, G, P> GeneticBreeder<C, S, G, P>
+where
+    C: VariationOperator<G>,
+    S: SelectionOperator,
+    G: Genome + Mutate,
+    P: Repair<G>,
+{
+    // Apply mating restriction on selected `parents`. Return None if the
+    // pair is refused.
+    fn mate<'a, R: Rng + Sized>(
+        &self,
+        mut parents: Vec<Member<'a, G>>,
+        population: &'a Population<G>,
+        rng: &mut R,
+    ) -> Option<Vec<Member<'a, G>>> {
+        let (metric, mating) = match &self.mating {
+            Some(x) if parents.len() >= 2 => x,
+            _ => return Some(parents),
+        };
+        let selector = self.selector.as_ref().expect("breeder has no selector");
+        let distance = |a: &Member<G>, b: &Member<G>| metric.distance(a.genome(), b.genome());
+        match *mating {
+            Mating::IncestPrevention { threshold } => {
+                if distance(&parents[0], &parents[1]) < threshold {
+                    return None;
+                }
+            }
+            Mating::Assortative { ncandidates } | Mating::Disassortative { ncandidates } => {
+                // exclude the first parent itself from candidate partners
+                let first = &parents[0];
+                let mut candidates = vec![];
+                for _ in 0..10 * ncandidates {
+                    if candidates.len() >= ncandidates {
+                        break;
+                    }
+                    let selected = selector.select_from(population, rng);
+                    candidates.extend(selected.into_iter().filter(|x| x.genome() != first.genome()));
+                }
+                candidates.truncate(ncandidates);
+                let scores: Vec<_> = candidates.iter().map(|x| distance(first, x)).collect();
+                let (i, _) = if matches!(mating, Mating::Assortative { .. }) {
+                    scores.iter().imin()?
+                } else {
+                    scores.iter().imax()?
+                };
+                parents[1] = candidates.swap_remove(i);
+            }
+        }
+        Some(parents)
+    }
 }
 
-impl<G, C, S> Breed<G> for GeneticBreeder<C, S, G>
+impl<G, C, S, P> Breed<G> for GeneticBreeder<C, S, G, P>
 where
     G: Genome + Mutate,
     C: VariationOperator<G>,
     S: SelectionOperator,
+    P: Repair<G>,
 {
     /// Breed `m` new genomes from parent population.
     fn breed<R: Rng + Sized>(
@@ -83,27 +224,124 @@ where
         rng: &mut R,
     ) -> Vec<G> {
         // let mut crossover = self.crossover.take().expect("breeder has no crossover");
-        let crossover = self.crossover.as_mut().expect("breeder has no crossover.");
-        let selector = self.selector.as_mut().expect("breeder has no selector");
+        let crossover = self.crossover.as_ref().expect("breeder has no crossover.");
+        let selector = self.selector.as_ref().expect("breeder has no selector");
 
         // loop until required number of genomes
-        let mut required_genomes = Vec::with_capacity(m);
-        while required_genomes.len() < m {
+        let mut children = Vec::with_capacity(m);
+        let mut origins = Vec::with_capacity(m);
+        let mut nrejected = 0;
+        let mut nrefused = 0;
+        while children.len() < m {
+            // avoid infinite loop when repair always fails
+            if nrejected > 100 * m {
+                warn!("Too many children rejected. Only bred {} genomes.", children.len());
+                break;
+            }
+            // mate anyway when restriction is too strict
             let parents = selector.select_from(population, rng);
+            let parents = if nrefused > 100 * m {
+                parents
+            } else if let Some(parents) = self.mate(parents, population, rng) {
+                parents
+            } else {
+                nrefused += 1;
+                continue;
+            };
             let new_genomes = crossover.breed_from(&parents, rng);
+            let origin = Origin::new("crossover", &parents);
             for mut g in new_genomes {
+                let mut origin = origin.clone();
                 // mutate one bit/one point randomly.
                 if rng.gen_range(0.0..1.0) < self.mut_prob {
                     g.mutate(1, rng);
+                    origin.operator.push_str("+mutation");
+                }
+                if self.repair.repair(&mut g, rng) {
+                    children.push(g);
+                    origins.push(origin);
+                } else {
+                    nrejected += 1;
                 }
-                required_genomes.push(g);
             }
         }
+        if nrejected > 0 {
+            info!("Rejected {nrejected} children that cannot be repaired.");
+        }
+        if nrefused > 0 {
+            info!("Refused {nrefused} pairs of parents by mating restriction.");
+        }
+        self.nrejected = nrejected;
+        self.nrejected_total += nrejected;
+        self.nrefused = nrefused;
+        self.origins = origins;
 
-        required_genomes
+        children
+    }
+
+    fn origins(&self) -> &[Origin] {
+        &self.origins
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        report.set("repair/rejected", self.nrejected);
+        report.set("repair/rejected_total", self.nrejected_total);
+        if self.mating.is_some() {
+            report.set("mating/refused", self.nrefused);
+        }
+        report
     }
 }
 
 // [[file:../../spdkit.note::*hypermutation][hypermutation:1]]
 
 // hypermutation:1 ends here
+
+// [[file:../../spdkit.note::*test][test:1]]
+#[test]
+fn test_mating_restriction() {
+    use crate::distance::Hamming;
+    use crate::operators::selection::RandomSelection;
+    use crate::operators::variation::OnePointCrossOver;
+
+    let genomes: Vec<_> = ["11111", "11110", "00000"].iter().map(|x| Binary::from_str(x)).collect();
+    let population = Population::build(OneMax.create(genomes), &mut crate::fitness::Maximize);
+    let mut rng = get_rng!();
+
+    let mating = Mating::IncestPrevention { threshold: 2.0 };
+    let mut breeder = GeneticBreeder::new()
+        .with_crossover(OnePointCrossOver)
+        .with_selector(RandomSelection::new(2))
+        .with_mating_restriction(Hamming, mating);
+    let genomes = breeder.breed(20, &population, &mut *rng);
+    assert!(genomes.len() >= 20);
+    assert!(breeder.nrefused() > 0);
+    assert_eq!(breeder.report().get("mating/refused").and_then(|x| x.as_count()), Some(breeder.nrefused()));
+
+    for mating in [Mating::Assortative { ncandidates: 3 }, Mating::Disassortative { ncandidates: 3 }] {
+        let mut breeder = GeneticBreeder::new()
+            .with_crossover(OnePointCrossOver)
+            .with_selector(RandomSelection::new(2))
+            .with_mating_restriction(Hamming, mating);
+        let genomes = breeder.breed(10, &population, &mut *rng);
+        assert!(genomes.len() >= 10);
+        assert_eq!(breeder.nrefused(), 0);
+    }
+
+    // the first parent is never mated with itself
+    let member = |s: &str| population.members().find(|m| m.genome().to_string() == s).unwrap();
+    for (mating, partner) in [
+        (Mating::Assortative { ncandidates: 60 }, "11110"),
+        (Mating::Disassortative { ncandidates: 60 }, "00000"),
+    ] {
+        let breeder = GeneticBreeder::new()
+            .with_crossover(OnePointCrossOver)
+            .with_selector(RandomSelection::new(2))
+            .with_mating_restriction(Hamming, mating);
+        let parents = breeder.mate(vec![member("11111"), member("00000")], &population, &mut *rng).unwrap();
+        assert_eq!(parents[0].genome().to_string(), "11111");
+        assert_eq!(parents[1].genome().to_string(), partner, "{mating:?}");
+    }
+}
+// test:1 ends here
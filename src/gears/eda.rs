@@ -0,0 +1,292 @@
+// [[file:../../spdkit.note::*imports][imports:1]]
+use crate::common::*;
+use crate::encoding::Binary;
+use crate::operators::*;
+use crate::random::*;
+use crate::report::Report;
+
+use super::*;
+// imports:1 ends here
+
+// [[file:../../spdkit.note::5d0c3a97][5d0c3a97]]
+// Sample a genome bit by bit from marginal probabilities of ones.
+fn sample_binary<R: Rng + Sized>(probabilities: &[f64], rng: &mut R) -> Binary {
+    probabilities.iter().map(|&p| rng.gen::<f64>() < p).collect()
+}
+
+fn genome_length(population: &Population<Binary>) -> usize {
+    population
+        .individuals()
+        .first()
+        .map(|x| x.genome().len())
+        .expect("empty population")
+}
+
+fn clamp_probabilities(probabilities: &mut [f64], margin: f64) {
+    for p in probabilities.iter_mut() {
+        *p = p.clamp(margin, 1.0 - margin);
+    }
+}
+
+/// Univariate marginal distribution algorithm (UMDA) for `Binary` genomes.
+///
+/// In each breeding, members are selected from population using `selector`,
+/// and new genomes are sampled from the frequencies of ones at each bit of
+/// the selected.
+///
+/// # Reference
+///
+/// * Mühlenbein and Paaß 1996, From recombination of genes to the estimation of distributions I. Binary parameters
+///
+#[derive(Debug, Clone)]
+pub struct Umda<S: SelectionOperator> {
+    selector: S,
+    margin: f64,
+    probabilities: Vec<f64>,
+}
+
+impl<S: SelectionOperator> Umda<S> {
+    /// Construct with `selector` for selecting members to learn from.
+    pub fn new(selector: S) -> Self {
+        Self {
+            selector,
+            margin: 0.0,
+            probabilities: vec![],
+        }
+    }
+
+    /// Keep marginal probabilities within [margin, 1 - margin] to avoid
+    /// premature fixation of bits. The default is 0.
+    pub fn margin(mut self, margin: f64) -> Self {
+        assert!((0.0..0.5).contains(&margin), "invalid margin: {margin}");
+        self.margin = margin;
+        self
+    }
+
+    /// Return marginal probabilities of ones learned in last breeding.
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+}
+
+impl<S: SelectionOperator> Breed<Binary> for Umda<S> {
+    fn breed<R: Rng + Sized>(&mut self, m: usize, population: &Population<Binary>, rng: &mut R) -> Vec<Binary> {
+        let n = genome_length(population);
+        let selected = self.selector.select_from(population, rng);
+        assert!(!selected.is_empty(), "no member selected.");
+
+        self.probabilities = (0..n)
+            .map(|i| selected.iter().filter(|x| x.genome()[i]).count() as f64 / selected.len() as f64)
+            .collect();
+        clamp_probabilities(&mut self.probabilities, self.margin);
+
+        (0..m).map(|_| sample_binary(&self.probabilities, rng)).collect()
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        report.set("eda/probabilities", self.probabilities.clone());
+        report
+    }
+}
+
+/// Population-based incremental learning (PBIL) for `Binary` genomes.
+///
+/// A probability vector is kept across generations. In each breeding, it is
+/// shifted toward the best members in population, randomly mutated, and new
+/// genomes are sampled from it.
+///
+/// # Reference
+///
+/// * Baluja 1994, Population-based incremental learning, CMU-CS-94-163
+///
+#[derive(Debug, Clone)]
+pub struct Pbil {
+    learning_rate: f64,
+    nbest: usize,
+    mutation_probability: f64,
+    mutation_shift: f64,
+    probabilities: Vec<f64>,
+}
+
+impl Default for Pbil {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.1,
+            nbest: 1,
+            mutation_probability: 0.02,
+            mutation_shift: 0.05,
+            probabilities: vec![],
+        }
+    }
+}
+
+impl Pbil {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set learning rate for shifting probabilities toward the best. The
+    /// default is 0.1.
+    pub fn learning_rate(mut self, r: f64) -> Self {
+        assert!(r > 0.0 && r <= 1.0, "invalid learning rate: {r}");
+        self.learning_rate = r;
+        self
+    }
+
+    /// Set the number of best members to learn from. The default is 1.
+    pub fn nbest(mut self, n: usize) -> Self {
+        assert!(n > 0, "invalid number of best members: {n}");
+        self.nbest = n;
+        self
+    }
+
+    /// Set the probability and the amount of random shift of each bit
+    /// probability. The defaults are 0.02 and 0.05.
+    pub fn mutation(mut self, probability: f64, shift: f64) -> Self {
+        assert!((0.0..=1.0).contains(&probability), "invalid mutation probability: {probability}");
+        assert!((0.0..=1.0).contains(&shift), "invalid mutation shift: {shift}");
+        self.mutation_probability = probability;
+        self.mutation_shift = shift;
+        self
+    }
+
+    /// Return current probability vector.
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+}
+
+impl Breed<Binary> for Pbil {
+    fn breed<R: Rng + Sized>(&mut self, m: usize, population: &Population<Binary>, rng: &mut R) -> Vec<Binary> {
+        let n = genome_length(population);
+        if self.probabilities.len() != n {
+            self.probabilities = vec![0.5; n];
+        }
+
+        let mut members: Vec<_> = population.members().collect();
+        members.sort_by(|a, b| float_ordering_maximize(&a.fitness_value(), &b.fitness_value()));
+        let r = self.learning_rate;
+        for m in members.iter().take(self.nbest) {
+            for (p, &b) in self.probabilities.iter_mut().zip(m.genome().iter()) {
+                *p = *p * (1.0 - r) + if b { r } else { 0.0 };
+            }
+        }
+        for p in self.probabilities.iter_mut() {
+            if rng.gen::<f64>() < self.mutation_probability {
+                let b = if rng.gen::<bool>() { 1.0 } else { 0.0 };
+                *p = *p * (1.0 - self.mutation_shift) + b * self.mutation_shift;
+            }
+        }
+
+        (0..m).map(|_| sample_binary(&self.probabilities, rng)).collect()
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        report.set("eda/probabilities", self.probabilities.clone());
+        report
+    }
+}
+
+/// Compact genetic algorithm (cGA) for `Binary` genomes.
+///
+/// A probability vector represents a virtual population of size `n`. In each
+/// breeding, two members are drawn at random from population to compete, and
+/// the probabilities of bits where they differ are shifted by 1/n toward the
+/// winner.
+///
+/// # Reference
+///
+/// * Harik, Lobo and Goldberg 1999, IEEE Trans. Evol. Comput., 3, 287
+///
+#[derive(Debug, Clone)]
+pub struct CompactGa {
+    virtual_size: usize,
+    probabilities: Vec<f64>,
+}
+
+impl CompactGa {
+    /// Construct with virtual population size `n`.
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0, "invalid virtual population size: {n}");
+        Self {
+            virtual_size: n,
+            probabilities: vec![],
+        }
+    }
+
+    /// Return current probability vector.
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+}
+
+impl Breed<Binary> for CompactGa {
+    fn breed<R: Rng + Sized>(&mut self, m: usize, population: &Population<Binary>, rng: &mut R) -> Vec<Binary> {
+        let n = genome_length(population);
+        if self.probabilities.len() != n {
+            self.probabilities = vec![0.5; n];
+        }
+
+        let members: Vec<_> = population.members().collect();
+        if members.len() >= 2 {
+            let pair: Vec<_> = members.choose_multiple(rng, 2).collect();
+            let (winner, loser) = if pair[0].fitness_value() >= pair[1].fitness_value() {
+                (pair[0], pair[1])
+            } else {
+                (pair[1], pair[0])
+            };
+            let step = 1.0 / self.virtual_size as f64;
+            for (i, p) in self.probabilities.iter_mut().enumerate() {
+                let (w, l) = (winner.genome()[i], loser.genome()[i]);
+                if w != l {
+                    *p = if w { *p + step } else { *p - step };
+                    *p = p.clamp(0.0, 1.0);
+                }
+            }
+        }
+
+        (0..m).map(|_| sample_binary(&self.probabilities, rng)).collect()
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        report.set("eda/probabilities", self.probabilities.clone());
+        report
+    }
+}
+// 5d0c3a97 ends here
+
+// [[file:../../spdkit.note::*test][test:1]]
+#[test]
+fn test_eda() {
+    use crate::operators::selection::*;
+
+    let genomes: Vec<_> = ["11110", "11011", "10100", "00000"].iter().map(|x| Binary::from_str(x)).collect();
+    let population = Population::build(OneMax.create(genomes), &mut crate::fitness::Maximize);
+    let mut rng = get_rng!();
+
+    let mut umda = Umda::new(ElitistSelection::new(2));
+    let genomes = umda.breed(6, &population, &mut *rng);
+    assert_eq!(genomes.len(), 6);
+    assert_eq!(umda.probabilities(), &[1.0, 1.0, 0.5, 1.0, 0.5]);
+    assert!(genomes.iter().all(|g| g[0] && g[1] && g[3]));
+
+    let mut pbil = Pbil::new().mutation(0.0, 0.0);
+    let genomes = pbil.breed(6, &population, &mut *rng);
+    assert_eq!(genomes.len(), 6);
+    let p = pbil.probabilities();
+    assert!(p[0] > 0.5 && p[1] > 0.5);
+    let report = pbil.report();
+    assert_eq!(report.get("eda/probabilities").and_then(|x| x.as_numbers()).unwrap().len(), 5);
+
+    let mut cga = CompactGa::new(10);
+    for _ in 0..20 {
+        cga.breed(2, &population, &mut *rng);
+    }
+    let p = cga.probabilities();
+    assert!(p.iter().all(|x| (0.0..=1.0).contains(x)));
+    assert!(p[0] >= 0.5);
+}
+// test:1 ends here
@@ -0,0 +1,35 @@
+// [[file:../../spdkit.note::*imports][imports:1]]
+use crate::common::*;
+use crate::individual::*;
+// imports:1 ends here
+
+// [[file:../../spdkit.note::d5a7f2c3][d5a7f2c3]]
+/// Local search for improving newly bred genomes before evaluation, such as
+/// geometry relaxation of molecular structures in memetic algorithm.
+pub trait LocalSearch<G: Genome>: Clone + Sync {
+    /// Improve `genome` by local search. Return the improved genome and its
+    /// objective value, or None if local search failed, in which case the
+    /// original genome will be evaluated as usual.
+    fn local_search(&self, genome: &G) -> Option<(G, f64)>;
+}
+
+/// How the result of local search is inherited by offspring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LearningMode {
+    /// Write the improved genome back into offspring.
+    Lamarckian,
+    /// Keep the original genome, but use the objective value of the improved
+    /// one.
+    Baldwinian,
+}
+
+/// A placeholder when no local search is applied.
+#[derive(Debug, Clone)]
+pub struct NoLocalSearch;
+
+impl<G: Genome> LocalSearch<G> for NoLocalSearch {
+    fn local_search(&self, _genome: &G) -> Option<(G, f64)> {
+        None
+    }
+}
+// d5a7f2c3 ends here
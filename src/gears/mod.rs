@@ -7,6 +7,7 @@
 use crate::individual::*;
 use crate::population::*;
 use crate::random::*;
+use crate::report::Report;
 
 /// Elemental gear for evolution engine
 pub trait Gear<G>
@@ -20,16 +21,58 @@ where
     fn forward(&mut self);
 }
 
+/// The origin of a newly bred genome.
+#[derive(Debug, Clone)]
+pub struct Origin {
+    /// The name of variation operator producing the genome.
+    pub operator: String,
+    /// The ids of the parent individuals.
+    pub parent_ids: Vec<usize>,
+}
+
+impl Origin {
+    /// Construct from the name of variation `operator` and the `parents`
+    /// members it was applied to.
+    pub fn new<G: Genome>(operator: &str, parents: &[Member<G>]) -> Self {
+        Self {
+            operator: operator.into(),
+            parent_ids: parents.iter().map(|m| m.individual.id()).collect(),
+        }
+    }
+}
+
 /// Breed `n` new genomes (not-evaluated individual) from parent population.
 pub trait Breed<G: Genome>: Clone {
     fn breed<R: Rng + Sized>(&mut self, n: usize, population: &Population<G>, rng: &mut R) -> Vec<G>;
+
+    /// Report statistics of the last breeding.
+    fn report(&self) -> Report {
+        Report::default()
+    }
+
+    /// Return the origins of genomes bred in last breeding, in the same order
+    /// as the genomes, for tracking lineage of individuals. The default
+    /// implementation returns nothing.
+    fn origins(&self) -> &[Origin] {
+        &[]
+    }
 }
 
+mod adaptive;
 mod breeder;
+mod eda;
+mod local_search;
+mod portfolio;
+mod speciation;
 mod survivor;
 mod valuer;
 
-pub use self::breeder::GeneticBreeder;
-pub use self::survivor::{Survive, Survivor};
+pub use self::adaptive::{AdaptiveBreeder, OperatorSelection};
+pub use self::breeder::{GeneticBreeder, Mating};
+pub use self::eda::{CompactGa, Pbil, Umda};
+pub use self::local_search::{LearningMode, LocalSearch, NoLocalSearch};
+pub use self::portfolio::PortfolioBreeder;
+pub use self::speciation::{SpeciatedBreeder, Species};
+pub use self::survivor::{DiversitySurvivor, Survive, Survivor};
 pub use self::valuer::Valuer;
 // mod.rs:1 ends here
@@ -0,0 +1,176 @@
+// [[file:../../spdkit.note::*imports][imports:1]]
+use std::sync::Arc;
+
+use crate::individual::*;
+use crate::operators::*;
+use crate::random::*;
+use crate::report::Report;
+
+use super::*;
+// imports:1 ends here
+
+// [[file:../../spdkit.note::b2e91a6d][b2e91a6d]]
+// A variation operator coupled with its own selector, in object-safe form.
+trait Variation<G: Genome> {
+    fn vary<'a>(&self, population: &'a Population<G>, rng: &mut dyn RngCore) -> (Vec<G>, Vec<Member<'a, G>>);
+}
+
+struct Coupled<C, S> {
+    operator: C,
+    selector: S,
+}
+
+impl<G, C, S> Variation<G> for Coupled<C, S>
+where
+    G: Genome,
+    C: VariationOperator<G>,
+    S: SelectionOperator,
+{
+    // Return children and their parents
+    fn vary<'a>(&self, population: &'a Population<G>, mut rng: &mut dyn RngCore) -> (Vec<G>, Vec<Member<'a, G>>) {
+        let parents = self.selector.select_from(population, &mut rng);
+        let children = self.operator.breed_from(&parents, &mut rng);
+        (children, parents)
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Entry<G: Genome> {
+    pub(crate) name: String,
+    pub(crate) weight: f64,
+    variation: Arc<dyn Variation<G>>,
+}
+
+impl<G: Genome> Entry<G> {
+    pub(crate) fn new<C, S>(name: &str, weight: f64, operator: C, selector: S) -> Self
+    where
+        C: VariationOperator<G> + 'static,
+        S: SelectionOperator + 'static,
+    {
+        assert!(weight.is_sign_positive(), "invalid operator weight: {weight}");
+        Self {
+            name: name.into(),
+            weight,
+            variation: Arc::new(Coupled { operator, selector }),
+        }
+    }
+
+    // Return children and their origin.
+    pub(crate) fn vary(&self, population: &Population<G>, rng: &mut dyn RngCore) -> (Vec<G>, Origin) {
+        let (children, parents) = self.variation.vary(population, rng);
+        (children, Origin::new(&self.name, &parents))
+    }
+}
+// b2e91a6d ends here
+
+// [[file:../../spdkit.note::*portfolio][portfolio:1]]
+/// A breeder holding a portfolio of weighted variation operators, e.g. 60%
+/// cut-and-splice crossover, 30% mutation and 10% random structures.
+///
+/// Each operator comes with its own selector, which also determines the
+/// number of parents it takes. In each variation step one operator is chosen
+/// with probability proportional to its weight.
+#[derive(Clone)]
+pub struct PortfolioBreeder<G: Genome> {
+    entries: Vec<Entry<G>>,
+    origins: Vec<Origin>,
+}
+
+impl<G: Genome> Default for PortfolioBreeder<G> {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+            origins: vec![],
+        }
+    }
+}
+
+impl<G: Genome> PortfolioBreeder<G> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add variation `operator` named as `name` with selection `weight`.
+    /// Parents are selected from population using `selector`.
+    pub fn with_operator<C, S>(mut self, name: &str, weight: f64, operator: C, selector: S) -> Self
+    where
+        C: VariationOperator<G> + 'static,
+        S: SelectionOperator + 'static,
+    {
+        self.entries.push(Entry::new(name, weight, operator, selector));
+        self
+    }
+}
+
+impl<G: Genome> Breed<G> for PortfolioBreeder<G> {
+    /// Breed `m` new genomes from parent population.
+    fn breed<R: Rng + Sized>(&mut self, m: usize, population: &Population<G>, rng: &mut R) -> Vec<G> {
+        assert!(!self.entries.is_empty(), "breeder has no operator.");
+
+        let mut children = Vec::with_capacity(m);
+        self.origins.clear();
+        while children.len() < m {
+            let entry = self
+                .entries
+                .choose_weighted(rng, |x| x.weight)
+                .unwrap_or_else(|e| panic!("Weighted selection failed: {:?}", e));
+            let (new_genomes, origin) = entry.vary(population, rng);
+            for g in new_genomes {
+                children.push(g);
+                self.origins.push(origin.clone());
+            }
+        }
+        children.truncate(m);
+        self.origins.truncate(m);
+
+        children
+    }
+
+    fn origins(&self) -> &[Origin] {
+        &self.origins
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        for entry in self.entries.iter() {
+            let n = self.origins.iter().filter(|o| o.operator == entry.name).count();
+            report.set(format!("operator/{}", entry.name), n);
+        }
+        report
+    }
+}
+// portfolio:1 ends here
+
+// [[file:../../spdkit.note::*test][test:1]]
+#[test]
+fn test_portfolio_breeder() {
+    use crate::encoding::Binary;
+    use crate::operators::selection::*;
+    use crate::operators::variation::*;
+
+    let genomes: Vec<_> = ["11110", "00011", "10101"].iter().map(|x| Binary::from_str(x)).collect();
+    let population = Population::build(OneMax.create(genomes), &mut crate::fitness::Maximize);
+
+    let mut breeder = PortfolioBreeder::new()
+        .with_operator("crossover", 0.6, OnePointCrossOver, RouletteWheelSelection::new(2))
+        .with_operator("mutation", 0.4, FlipBitMutation::new(), RandomSelection::new(1));
+    let mut rng = get_rng!();
+    let genomes = breeder.breed(7, &population, &mut *rng);
+    assert_eq!(genomes.len(), 7);
+    assert_eq!(breeder.origins().len(), 7);
+    let ids: Vec<_> = population.individuals().iter().map(|x| x.id()).collect();
+    for origin in breeder.origins() {
+        assert!(origin.parent_ids.iter().all(|id| ids.contains(id)));
+        match origin.operator.as_str() {
+            "crossover" => assert_eq!(origin.parent_ids.len(), 2),
+            "mutation" => assert_eq!(origin.parent_ids.len(), 1),
+            _ => unreachable!(),
+        }
+    }
+
+    let report = breeder.report();
+    let n1 = report.get("operator/crossover").and_then(|x| x.as_count()).unwrap();
+    let n2 = report.get("operator/mutation").and_then(|x| x.as_count()).unwrap();
+    assert_eq!(n1 + n2, 7);
+}
+// test:1 ends here
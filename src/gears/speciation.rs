@@ -0,0 +1,323 @@
+// [[file:../../spdkit.note::*imports][imports:1]]
+use crate::common::*;
+use crate::distance::GenomeDistance;
+use crate::individual::*;
+use crate::random::*;
+use crate::report::Report;
+
+use super::*;
+// imports:1 ends here
+
+// [[file:../../spdkit.note::c61d9e2a][c61d9e2a]]
+/// A species of genomes within compatibility distance of its representative.
+#[derive(Debug, Clone)]
+pub struct Species<G> {
+    /// The unique id of species.
+    pub id: usize,
+    /// The genome representing this species, which is the best member in
+    /// last breeding.
+    pub representative: G,
+    /// Indices of members in the population of last breeding.
+    pub members: Vec<usize>,
+    /// The best objective value ever found in this species.
+    pub best: Option<f64>,
+    /// The number of generations without improvement of the best objective
+    /// value.
+    pub nstagnant: usize,
+}
+
+/// NEAT-style speciated breeder.
+///
+/// In each breeding, population members are clustered into species: a member
+/// joins the first species whose representative is within `threshold` of
+/// compatibility distance, or founds a new species. Fitness is shared within
+/// each species by dividing by species size, and offspring slots are
+/// allocated to species in proportion to their sums of adjusted fitness.
+/// Children of each species are bred from its members by the inner
+/// `breeder`. Species whose best objective value is not improved for a
+/// number of generations are removed, except the one holding the best member
+/// assigned to species. The representatives of removed species are
+/// remembered, and later members within their compatibility distance are
+/// excluded from breeding, so that stagnant lineages do not come back as new
+/// species. If all members are excluded, children are bred from the whole
+/// population by the inner `breeder`.
+///
+/// Objective values are compared in the direction agreeing with fitness in
+/// population, i.e. the fittest member has the better objective value.
+///
+/// # Reference
+///
+/// * Stanley and Miikkulainen 2002, Evol. Comput., 10, 99
+///
+#[derive(Clone)]
+pub struct SpeciatedBreeder<G: Genome, B: Breed<G>, D: GenomeDistance<G> + Clone> {
+    breeder: B,
+    metric: D,
+    threshold: f64,
+    stagnation_limit: usize,
+
+    species: Vec<Species<G>>,
+    next_id: usize,
+    // ids and representatives of removed species
+    extinct: Vec<(usize, G)>,
+
+    // statistics of last breeding
+    membership: Vec<usize>,
+    offspring: Vec<usize>,
+    nremoved: usize,
+    nexcluded: usize,
+    origins: Vec<Origin>,
+}
+
+impl<G, B, D> SpeciatedBreeder<G, B, D>
+where
+    G: Genome,
+    B: Breed<G>,
+    D: GenomeDistance<G> + Clone,
+{
+    /// Construct with inner `breeder` for breeding within species, and
+    /// compatibility distance `metric` with `threshold`.
+    pub fn new(breeder: B, metric: D, threshold: f64) -> Self {
+        assert!(threshold > 0.0, "invalid compatibility threshold: {threshold}");
+        Self {
+            breeder,
+            metric,
+            threshold,
+            stagnation_limit: 15,
+            species: vec![],
+            next_id: 0,
+            extinct: vec![],
+            membership: vec![],
+            offspring: vec![],
+            nremoved: 0,
+            nexcluded: 0,
+            origins: vec![],
+        }
+    }
+
+    /// Remove species stagnating for `n` generations. The default is 15.
+    pub fn stagnation_limit(mut self, n: usize) -> Self {
+        assert!(n > 0, "invalid stagnation limit: {n}");
+        self.stagnation_limit = n;
+        self
+    }
+
+    /// Return species found in last breeding.
+    pub fn species(&self) -> &[Species<G>] {
+        &self.species
+    }
+}
+
+impl<G, B, D> SpeciatedBreeder<G, B, D>
+where
+    G: Genome,
+    B: Breed<G>,
+    D: GenomeDistance<G> + Clone,
+{
+    // Assign population members into species. Members close to removed
+    // species are excluded.
+    fn speciate(&mut self, population: &Population<G>) {
+        for s in self.species.iter_mut() {
+            s.members.clear();
+        }
+        self.membership.clear();
+        self.nexcluded = 0;
+        for (i, indv) in population.individuals().iter().enumerate() {
+            let genome = indv.genome();
+            let is_compatible = |r: &G| self.metric.distance(r, genome) < self.threshold;
+            let k = match self.species.iter().position(|s| is_compatible(&s.representative)) {
+                Some(k) => k,
+                None => {
+                    if let Some(id) = self.extinct.iter().find(|(_, r)| is_compatible(r)).map(|x| x.0) {
+                        self.membership.push(id);
+                        self.nexcluded += 1;
+                        continue;
+                    }
+                    self.species.push(Species {
+                        id: self.next_id,
+                        representative: genome.clone(),
+                        members: vec![],
+                        best: None,
+                        nstagnant: 0,
+                    });
+                    self.next_id += 1;
+                    self.species.len() - 1
+                }
+            };
+            self.species[k].members.push(i);
+            self.membership.push(self.species[k].id);
+        }
+        self.species.retain(|s| !s.members.is_empty());
+    }
+
+    // Update representatives and stagnation counters, and remove stagnant
+    // species.
+    fn cull(&mut self, population: &Population<G>, fitness: &[f64]) {
+        let indvs = population.individuals();
+        // the direction of objective values agreeing with fitness
+        let sense = match (fitness.iter().imax(), fitness.iter().imin()) {
+            (Some((i, _)), Some((j, _))) if indvs[i].objective_value() < indvs[j].objective_value() => -1.0,
+            _ => 1.0,
+        };
+        // the best member not excluded by removed species
+        let ibest = self
+            .species
+            .iter()
+            .flat_map(|s| s.members.iter().copied())
+            .max_by(|&a, &b| fitness[a].total_cmp(&fitness[b]));
+        for s in self.species.iter_mut() {
+            let i = s.members.iter().copied().max_by(|&a, &b| fitness[a].total_cmp(&fitness[b])).unwrap();
+            s.representative = indvs[i].genome().clone();
+            let value = indvs[i].objective_value();
+            match s.best {
+                Some(best) if sense * (value - best) <= 0.0 => s.nstagnant += 1,
+                _ => {
+                    s.best = Some(value);
+                    s.nstagnant = 0;
+                }
+            }
+        }
+        let limit = self.stagnation_limit;
+        let (kept, removed): (Vec<_>, Vec<_>) = std::mem::take(&mut self.species)
+            .into_iter()
+            .partition(|s| s.nstagnant < limit || ibest.is_some_and(|i| s.members.contains(&i)));
+        self.species = kept;
+        self.nremoved = removed.len();
+        self.extinct.extend(removed.into_iter().map(|s| (s.id, s.representative)));
+    }
+
+    // Allocate `m` offspring slots by largest remainder in proportion to sums
+    // of adjusted fitness of species.
+    fn allocate(&self, m: usize, fitness: &[f64]) -> Vec<usize> {
+        let shares: Vec<f64> = self
+            .species
+            .iter()
+            .map(|s| s.members.iter().map(|&i| fitness[i]).sum::<f64>() / s.members.len() as f64)
+            .collect();
+        let total: f64 = shares.iter().sum();
+        let quotas: Vec<f64> = if total > 0.0 {
+            shares.iter().map(|x| x / total * m as f64).collect()
+        } else {
+            vec![m as f64 / shares.len() as f64; shares.len()]
+        };
+        let mut slots: Vec<usize> = quotas.iter().map(|q| q.floor() as usize).collect();
+        let mut order: Vec<_> = (0..quotas.len()).collect();
+        order.sort_by(|&i, &j| float_ordering_maximize(&quotas[i].fract(), &quotas[j].fract()));
+        let nleft = m - slots.iter().sum::<usize>();
+        for &i in order.iter().cycle().take(nleft) {
+            slots[i] += 1;
+        }
+        slots
+    }
+}
+
+impl<G, B, D> Breed<G> for SpeciatedBreeder<G, B, D>
+where
+    G: Genome,
+    B: Breed<G>,
+    D: GenomeDistance<G> + Clone,
+{
+    /// Breed `m` new genomes from parent population.
+    fn breed<R: Rng + Sized>(&mut self, m: usize, population: &Population<G>, rng: &mut R) -> Vec<G> {
+        let fitness: Vec<_> = population.members().map(|x| x.fitness_value()).collect();
+        self.speciate(population);
+        self.cull(population, &fitness);
+        self.origins.clear();
+        if self.species.is_empty() {
+            warn!("All members are excluded from species. Breed from whole population.");
+            self.offspring.clear();
+            let children = self.breeder.breed(m, population, rng);
+            self.origins.extend(self.breeder.origins().iter().cloned());
+            return children;
+        }
+        self.offspring = self.allocate(m, &fitness);
+
+        let mut children = Vec::with_capacity(m);
+        for (s, &k) in self.species.iter().zip(&self.offspring) {
+            if k > 0 {
+                let mut sub = population.subset(&s.members);
+                sub.weight_with(1.0 / s.members.len() as f64);
+                children.extend(self.breeder.breed(k, &sub, rng).into_iter().take(k));
+                self.origins.extend(self.breeder.origins().iter().take(k).cloned());
+            }
+        }
+        children
+    }
+
+    fn origins(&self) -> &[Origin] {
+        &self.origins
+    }
+
+    fn report(&self) -> Report {
+        let mut report = self.breeder.report();
+        report.set("species/count", self.species.len());
+        report.set("species/ids", self.species.iter().map(|s| s.id).collect::<Vec<_>>());
+        report.set("species/sizes", self.species.iter().map(|s| s.members.len()).collect::<Vec<_>>());
+        report.set("species/offspring", self.offspring.clone());
+        report.set("species/membership", self.membership.clone());
+        report.set("species/removed", self.nremoved);
+        report.set("species/excluded", self.nexcluded);
+        report
+    }
+}
+// c61d9e2a ends here
+
+// [[file:../../spdkit.note::*test][test:1]]
+#[test]
+fn test_speciated_breeder() {
+    use crate::distance::Hamming;
+    use crate::encoding::Binary;
+    use crate::operators::selection::*;
+    use crate::operators::variation::*;
+
+    let genomes: Vec<_> = ["11110", "11111", "00001", "00000", "00011"].iter().map(|x| Binary::from_str(x)).collect();
+    let population = Population::build(OneMax.create(genomes), &mut crate::fitness::Maximize);
+
+    let inner = GeneticBreeder::new()
+        .with_crossover(OnePointCrossOver)
+        .with_selector(RouletteWheelSelection::new(2));
+    let mut breeder = SpeciatedBreeder::new(inner, Hamming, 2.5).stagnation_limit(2);
+    let mut rng = get_rng!();
+    let genomes = breeder.breed(10, &population, &mut *rng);
+    assert_eq!(genomes.len(), 10);
+    assert_eq!(breeder.species().len(), 2);
+
+    let report = breeder.report();
+    let mut sizes = report.get("species/sizes").and_then(|x| x.as_counts()).unwrap().to_vec();
+    sizes.sort();
+    assert_eq!(sizes, vec![2, 3]);
+    assert_eq!(report.get("species/membership").and_then(|x| x.as_counts()).unwrap().len(), 5);
+    let offspring = report.get("species/offspring").and_then(|x| x.as_counts()).unwrap();
+    assert_eq!(offspring.iter().sum::<usize>(), 10);
+    // the species of ones has larger adjusted fitness
+    let i = breeder.species().iter().position(|s| s.representative[0]).unwrap();
+    assert!(offspring[i] > offspring[1 - i]);
+
+    // the stagnant species without the best member is removed
+    let stagnant = breeder.species()[1 - i].id;
+    for _ in 0..2 {
+        breeder.breed(10, &population, &mut *rng);
+    }
+    let report = breeder.report();
+    assert_eq!(report.get("species/removed").and_then(|x| x.as_count()), Some(1));
+    assert_eq!(breeder.species().len(), 1);
+    assert!(breeder.species()[0].representative[0]);
+
+    // and its members get no offspring slots afterwards
+    breeder.breed(10, &population, &mut *rng);
+    let report = breeder.report();
+    assert_eq!(report.get("species/removed").and_then(|x| x.as_count()), Some(0));
+    assert_eq!(report.get("species/excluded").and_then(|x| x.as_count()), Some(3));
+    assert_eq!(report.get("species/offspring").and_then(|x| x.as_counts()), Some(&[10][..]));
+    let membership = report.get("species/membership").and_then(|x| x.as_counts()).unwrap();
+    assert_eq!(membership.iter().filter(|&&id| id == stagnant).count(), 3);
+
+    // breed from whole population if all members are excluded
+    let genomes: Vec<_> = ["00001", "00000", "00011"].iter().map(|x| Binary::from_str(x)).collect();
+    let population = Population::build(OneMax.create(genomes), &mut crate::fitness::Maximize);
+    let genomes = breeder.breed(10, &population, &mut *rng);
+    assert_eq!(genomes.len(), 10);
+    assert!(breeder.species().is_empty());
+    assert_eq!(breeder.report().get("species/excluded").and_then(|x| x.as_count()), Some(3));
+}
+// test:1 ends here
@@ -5,6 +5,7 @@ use crate::individual::*;
 use crate::operators::*;
 use crate::population::*;
 use crate::random::*;
+use crate::report::Report;
 
 use super::*;
 // imports:1 ends here
@@ -13,6 +14,11 @@ use super::*;
 /// Member supplanting by removing bad performing individuals.
 pub trait Survive<G: Genome>: Clone {
     fn survive<R: Rng + Sized>(&mut self, population: Population<G>, rng: &mut R) -> Vec<Individual<G>>;
+
+    /// Report statistics of the last survival.
+    fn report(&self) -> Report {
+        Report::default()
+    }
 }
 
 #[derive(Clone)]
@@ -60,3 +66,89 @@ where
     }
 }
 // base:1 ends here
+
+// [[file:../../spdkit.note::6c1d4e8b][6c1d4e8b]]
+use crate::distance::GenomeDistance;
+
+/// A survivor preserving structural diversity: candidates are visited from
+/// the best to the worst, and any candidate too close to an already kept
+/// member is removed as a near-duplicate.
+#[derive(Clone)]
+pub struct DiversitySurvivor<D> {
+    metric: D,
+    threshold: f64,
+    nremoved: usize,
+}
+
+impl<D> DiversitySurvivor<D> {
+    /// Construct with genome distance `metric` and the `threshold` below which
+    /// two genomes are considered as near-duplicates.
+    pub fn new(metric: D, threshold: f64) -> Self {
+        assert!(threshold.is_sign_positive(), "invalid threshold: {threshold}");
+        Self {
+            metric,
+            threshold,
+            nremoved: 0,
+        }
+    }
+
+    /// Return the number of near-duplicates removed in last survival.
+    pub fn nremoved(&self) -> usize {
+        self.nremoved
+    }
+}
+
+impl<G, D> Survive<G> for DiversitySurvivor<D>
+where
+    G: Genome,
+    D: GenomeDistance<G> + Clone,
+{
+    fn survive<R: Rng + Sized>(&mut self, population: Population<G>, _rng: &mut R) -> Vec<Individual<G>> {
+        let mut members: Vec<_> = population.members().collect();
+        members.sort_by_fitness();
+
+        let nlimit = population.size_limit();
+        let mut kept: Vec<Member<G>> = Vec::with_capacity(nlimit);
+        let mut nremoved = 0;
+        for m in members {
+            if kept.len() >= nlimit {
+                break;
+            }
+            if kept.iter().any(|k| self.metric.distance(k.genome(), m.genome()) < self.threshold) {
+                nremoved += 1;
+            } else {
+                kept.push(m);
+            }
+        }
+        if nremoved > 0 {
+            info!("Removed {nremoved} near-duplicates.");
+        }
+        self.nremoved = nremoved;
+
+        kept.into_iter().map(|m| m.individual.to_owned()).collect()
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        report.set("survivor/near_duplicates", self.nremoved);
+        report
+    }
+}
+
+#[test]
+fn test_diversity_survivor() {
+    use crate::distance::Hamming;
+
+    let genomes: Vec<_> = ["11110", "11111", "00001", "00011"].iter().map(|x| Binary::from_str(x)).collect();
+    let indvs = OneMax.create(genomes);
+    let population = Population::build(indvs, &mut crate::fitness::Maximize).with_size_limit(3);
+
+    let mut rng = get_rng!();
+    let mut survivor = DiversitySurvivor::new(Hamming, 2.0);
+    let survived = survivor.survive(population, &mut *rng);
+    assert_eq!(survived.len(), 2);
+    assert_eq!(survivor.nremoved(), 2);
+    assert_eq!(survived[0].genome().to_string(), "11111");
+    assert_eq!(survived[1].genome().to_string(), "00011");
+}
+// 6c1d4e8b ends here
@@ -61,6 +61,16 @@ where
         }
     }
 
+    /// Create individuals from genomes with known objective values, e.g.
+    /// obtained from local search.
+    pub fn create_evaluated_individuals(&self, genomes: Vec<(G, f64)>) -> Vec<Individual<G>> {
+        if let Some(creator) = &self.creator {
+            creator.create_evaluated(genomes)
+        } else {
+            panic!("creator not set!");
+        }
+    }
+
     /// Build a population from individuals.
     pub fn build_population(&mut self, indvs: Vec<Individual<G>>) -> Population<G> {
         if let Some(fitness) = &mut self.fitness {
@@ -69,5 +79,25 @@ where
             panic!("fitness not set!");
         }
     }
+
+    /// Update internal state of fitness function from the population of a
+    /// new generation.
+    pub fn update_fitness(&mut self, population: &Population<G>) {
+        if let Some(fitness) = &mut self.fitness {
+            fitness.update(population);
+        } else {
+            panic!("fitness not set!");
+        }
+    }
+
+    /// Evaluate fitness values of individuals using a copy of fitness
+    /// function, so that its internal state (if any) is left untouched.
+    pub fn evaluate_fitness(&self, indvs: &[Individual<G>]) -> Vec<f64> {
+        if let Some(fitness) = &self.fitness {
+            fitness.clone().evaluate(indvs)
+        } else {
+            panic!("fitness not set!");
+        }
+    }
 }
 // base:1 ends here
@@ -0,0 +1,149 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use crate::common::*;
+use crate::individual::*;
+use crate::population::*;
+// imports:1 ends here
+
+// [[file:../spdkit.note::e4a7c093][e4a7c093]]
+/// The record of an individual ever seen in evolution.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    /// How the individual was made.
+    pub lineage: Lineage,
+    /// The evaluated objective value of the individual.
+    pub objective_value: f64,
+}
+
+/// The genealogy of an evolution run: every individual that has appeared in
+/// a population, with links to its parents.
+///
+/// The genealogy can be exported as a DOT graph for visualization with
+/// Graphviz, or as JSON via `Configure`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Genealogy {
+    records: BTreeMap<usize, Record>,
+}
+
+impl Genealogy {
+    /// Record individuals in `population` not seen before.
+    pub fn update<G: Genome>(&mut self, population: &Population<G>) {
+        for indv in population.individuals() {
+            self.records.entry(indv.id()).or_insert_with(|| Record {
+                lineage: indv.lineage().clone(),
+                objective_value: indv.objective_value(),
+            });
+        }
+    }
+
+    /// Return the number of recorded individuals.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Return true if no individual is recorded.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Return the record of individual `id`.
+    pub fn get(&self, id: usize) -> Option<&Record> {
+        self.records.get(&id)
+    }
+
+    /// Return all records in the order of individual ids.
+    pub fn records(&self) -> impl Iterator<Item = &Record> {
+        self.records.values()
+    }
+
+    /// Return the ids of all recorded ancestors of individual `id`, in
+    /// ascending order.
+    pub fn ancestors(&self, id: usize) -> Vec<usize> {
+        let mut found = BTreeSet::new();
+        let mut stack = vec![id];
+        while let Some(i) = stack.pop() {
+            if let Some(record) = self.records.get(&i) {
+                for &p in record.lineage.parents.iter() {
+                    if self.records.contains_key(&p) && found.insert(p) {
+                        stack.push(p);
+                    }
+                }
+            }
+        }
+        found.into_iter().collect()
+    }
+
+    /// Return the family tree of individual `id`, including itself and all
+    /// its ancestors.
+    pub fn pedigree(&self, id: usize) -> Self {
+        let records = self
+            .ancestors(id)
+            .into_iter()
+            .chain(std::iter::once(id))
+            .filter_map(|i| Some((i, self.records.get(&i)?.clone())))
+            .collect();
+        Self { records }
+    }
+
+    /// Export as a graph in DOT language, with edges from parents to
+    /// children labeled by variation operators.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph genealogy {{").unwrap();
+        writeln!(dot, "    node [shape=box];").unwrap();
+        for (id, record) in self.records.iter() {
+            let birth = record.lineage.birth.map(|x| x.to_string()).unwrap_or_else(|| "-".into());
+            writeln!(
+                dot,
+                "    {id} [label=\"#{id}\\ngeneration {birth}\\n{:.4}\"];",
+                record.objective_value
+            )
+            .unwrap();
+        }
+        for (id, record) in self.records.iter() {
+            for p in record.lineage.parents.iter().filter(|p| self.records.contains_key(p)) {
+                writeln!(dot, "    {p} -> {id} [label=\"{}\"];", record.lineage.operator).unwrap();
+            }
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+// e4a7c093 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[test]
+fn test_genealogy() -> Result<()> {
+    use crate::encoding::Binary;
+    use crate::fitness::Maximize;
+
+    let genomes: Vec<_> = ["1100", "0011", "1111"].iter().map(|x| Binary::from_str(x)).collect();
+    let mut indvs = OneMax.create(genomes);
+    indvs.sort_by_key(|x| x.objective_value() as usize);
+    let (a, b) = (indvs[0].id(), indvs[1].id());
+    let lineage = indvs[2].lineage_mut();
+    lineage.parents = vec![a, b];
+    lineage.operator = "crossover".into();
+    let c = indvs[2].id();
+    let population = Population::build(indvs, &mut Maximize);
+
+    let mut genealogy = Genealogy::default();
+    genealogy.update(&population);
+    assert_eq!(genealogy.len(), 3);
+    assert_eq!(genealogy.ancestors(c), vec![a.min(b), a.max(b)]);
+    assert!(genealogy.ancestors(a).is_empty());
+    assert_eq!(genealogy.pedigree(a).len(), 1);
+
+    let dot = genealogy.to_dot();
+    assert!(dot.starts_with("digraph"));
+    assert!(dot.contains(&format!("{a} -> {c} [label=\"crossover\"]")));
+
+    let json = genealogy.to_json()?;
+    let genealogy = Genealogy::from_json(&json)?;
+    assert_eq!(genealogy.get(c).unwrap().lineage.parents, vec![a, b]);
+
+    Ok(())
+}
+// test:1 ends here
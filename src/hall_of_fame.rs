@@ -0,0 +1,126 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use crate::common::*;
+use crate::distance::GenomeDistance;
+use crate::fitness::*;
+use crate::gears::Valuer;
+use crate::individual::*;
+use crate::population::*;
+// imports:1 ends here
+
+// [[file:../spdkit.note::e8a4c06b][e8a4c06b]]
+/// An archive of the best unique individuals ever seen during evolution.
+///
+/// Individuals are ranked by the fitness function of `Valuer`. Two
+/// individuals are considered as duplicates if they have the same genome, or,
+/// when a genome distance is set, if their distance is below a threshold.
+pub struct HallOfFame<G>
+where
+    G: Genome,
+{
+    capacity: usize,
+    // sorted from the best to the worst
+    individuals: Vec<Individual<G>>,
+    metric: Option<(Box<dyn GenomeDistance<G>>, f64)>,
+}
+
+impl<G> HallOfFame<G>
+where
+    G: Genome,
+{
+    /// Construct an archive keeping at most `k` best individuals.
+    pub fn new(k: usize) -> Self {
+        assert!(k > 0, "invalid hall of fame size: {k}");
+        Self {
+            capacity: k,
+            individuals: vec![],
+            metric: None,
+        }
+    }
+
+    /// Consider two individuals as duplicates if the distance between their
+    /// genomes is less than `threshold`.
+    pub fn with_distance(mut self, metric: impl GenomeDistance<G> + 'static, threshold: f64) -> Self {
+        self.metric = Some((Box::new(metric), threshold));
+        self
+    }
+
+    /// Return the archived individuals from the best to the worst.
+    pub fn individuals(&self) -> &[Individual<G>] {
+        &self.individuals
+    }
+
+    /// Return the archived individuals from the best to the worst, consuming
+    /// the archive.
+    pub fn into_individuals(self) -> Vec<Individual<G>> {
+        self.individuals
+    }
+
+    /// Return the best individual in archive.
+    pub fn best(&self) -> Option<&Individual<G>> {
+        self.individuals.first()
+    }
+
+    fn is_duplicate(&self, kept: &[Individual<G>], indv: &Individual<G>) -> bool {
+        kept.iter().any(|k| {
+            if k.genome() == indv.genome() {
+                return true;
+            }
+            match &self.metric {
+                Some((metric, threshold)) => metric.distance(k.genome(), indv.genome()) < *threshold,
+                None => false,
+            }
+        })
+    }
+
+    /// Update archive with individuals in `population`.
+    pub fn update<F, C>(&mut self, population: &Population<G>, valuer: &Valuer<G, F, C>)
+    where
+        F: EvaluateFitness<G>,
+        C: EvaluateObjectiveValue<G>,
+    {
+        let mut candidates = std::mem::take(&mut self.individuals);
+        candidates.extend_from_slice(population.individuals());
+        let values = valuer.evaluate_fitness(&candidates);
+
+        let mut order: Vec<_> = (0..candidates.len()).collect();
+        order.sort_by(|&i, &j| float_ordering_maximize(&values[i], &values[j]));
+
+        let mut kept = Vec::with_capacity(self.capacity);
+        for i in order {
+            if kept.len() >= self.capacity {
+                break;
+            }
+            if !self.is_duplicate(&kept, &candidates[i]) {
+                kept.push(candidates[i].clone());
+            }
+        }
+        self.individuals = kept;
+    }
+}
+// e8a4c06b ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::distance::Hamming;
+    use crate::encoding::Binary;
+
+    #[test]
+    fn test_hall_of_fame() {
+        let mut valuer = Valuer::new().with_fitness(Maximize).with_creator(OneMax);
+        let mut hof = HallOfFame::new(2).with_distance(Hamming, 2.0);
+
+        let genomes: Vec<_> = ["11110", "00011"].iter().map(|x| Binary::from_str(x)).collect();
+        let population = valuer.build_population(valuer.create_individuals(genomes));
+        hof.update(&population, &valuer);
+        assert_eq!(hof.individuals().len(), 2);
+
+        let genomes: Vec<_> = ["11111", "00001"].iter().map(|x| Binary::from_str(x)).collect();
+        let population = valuer.build_population(valuer.create_individuals(genomes));
+        hof.update(&population, &valuer);
+        let best: Vec<_> = hof.individuals().iter().map(|x| x.genome().to_string()).collect();
+        assert_eq!(best, ["11111", "00011"]);
+    }
+}
+// test:1 ends here
@@ -1,5 +1,6 @@
 // [[file:../spdkit.note::*imports][imports:1]]
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::encoding::*;
 use crate::random::*;
@@ -19,6 +20,35 @@ where
 {
     raw_score: f64,
     genome: G,
+    violation: Option<f64>,
+    lineage: Lineage,
+}
+
+/// The record of how an individual was made.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lineage {
+    /// The unique id of the individual within this process.
+    pub id: usize,
+    /// The generation in which the individual was born, or None if it is not
+    /// yet placed into an evolving population.
+    pub birth: Option<usize>,
+    /// The ids of parents. Empty for individuals created from scratch.
+    pub parents: Vec<usize>,
+    /// The name of variation operator creating the individual. Empty for
+    /// individuals created from scratch.
+    pub operator: String,
+}
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+impl Lineage {
+    // Create a lineage record with a new unique id.
+    fn new() -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            ..Default::default()
+        }
+    }
 }
 
 /// Evaluate the objective value of an individual.
@@ -29,6 +59,15 @@ where
     G: Genome,
 {
     fn evaluate(&self, genome: &G) -> f64;
+
+    /// Evaluate the degree of constraint violation of `genome`. A value of
+    /// zero means the genome is feasible, and the larger the value, the more
+    /// severe the violation.
+    ///
+    /// The default implementation returns None, that is, unconstrained.
+    fn evaluate_violation(&self, _genome: &G) -> Option<f64> {
+        None
+    }
 }
 
 impl<G> Individual<G>
@@ -42,7 +81,27 @@ where
         E: EvaluateObjectiveValue<G>,
     {
         let raw_score = func.evaluate(&genome);
-        Self { genome, raw_score }
+        let violation = func.evaluate_violation(&genome);
+        Self {
+            genome,
+            raw_score,
+            violation,
+            lineage: Lineage::new(),
+        }
+    }
+
+    /// Return the unique id of this individual.
+    pub fn id(&self) -> usize {
+        self.lineage.id
+    }
+
+    /// Return the lineage record of this individual.
+    pub fn lineage(&self) -> &Lineage {
+        &self.lineage
+    }
+
+    pub(crate) fn lineage_mut(&mut self) -> &mut Lineage {
+        &mut self.lineage
     }
 
     /// Return genome of this individual.
@@ -63,6 +122,17 @@ where
     pub fn objective_value(&self) -> f64 {
         self.raw_score
     }
+
+    /// Return the evaluated constraint violation of this individual, or None
+    /// if the individual is not subject to any constraint.
+    pub fn constraint_violation(&self) -> Option<f64> {
+        self.violation
+    }
+
+    /// Return true if this individual violates no constraint.
+    pub fn is_feasible(&self) -> bool {
+        !matches!(self.violation, Some(v) if v > 0.0)
+    }
 }
 
 impl<G> AsRef<Individual<G>> for Individual<G>
@@ -84,6 +154,9 @@ where
     G: Genome,
 {
     fn create(&self, genomes: impl IntoIterator<Item = G>) -> Vec<Individual<G>>;
+
+    /// Create individuals from genomes with known objective values.
+    fn create_evaluated(&self, genomes: impl IntoIterator<Item = (G, f64)>) -> Vec<Individual<G>>;
 }
 
 impl<G, T> Create<G> for T
@@ -107,7 +180,34 @@ where
             .into_par_iter()
             .map(|g| {
                 let raw_score = self.evaluate(&g);
-                Individual { genome: g, raw_score }
+                let violation = self.evaluate_violation(&g);
+                Individual {
+                    genome: g,
+                    raw_score,
+                    violation,
+                    lineage: Lineage::new(),
+                }
+            })
+            .collect()
+    }
+
+    /// Create individuals from genomes with known objective values. Only the
+    /// constraint violation will be evaluated.
+    fn create_evaluated(&self, genomes: impl IntoIterator<Item = (G, f64)>) -> Vec<Individual<G>> {
+        // remove possible duplicates
+        let mut seen = std::collections::HashSet::new();
+        let genomes: Vec<_> = genomes.into_iter().filter(|(g, _)| seen.insert(g.clone())).collect();
+
+        genomes
+            .into_par_iter()
+            .map(|(g, raw_score)| {
+                let violation = self.evaluate_violation(&g);
+                Individual {
+                    genome: g,
+                    raw_score,
+                    violation,
+                    lineage: Lineage::new(),
+                }
             })
             .collect()
     }
@@ -164,19 +164,38 @@ pub mod common {
 #[macro_use]
 pub mod random; // the mod order is important for get_rng! macro
 
+pub mod annealing;
+pub mod basin_hopping;
+pub mod cma_es;
+pub mod constraint;
+pub mod differential_evolution;
+pub mod distance;
+pub mod diversity;
 pub mod encoding;
 pub mod engine;
 pub mod fitness;
 pub mod gears;
+pub mod genealogy;
+pub mod hall_of_fame;
 pub mod individual;
+pub mod map_elites;
+pub mod niching;
+pub mod novelty;
 pub mod operators;
+pub mod parallel_tempering;
+pub mod particle_swarm;
 pub mod population;
+pub mod report;
+pub mod restart;
+pub mod tabu_search;
 pub mod termination;
+pub mod units;
 
-mod annealing;
 mod fingerprint;
 mod graph6;
 mod similarity;
+#[cfg(test)]
+mod test_utils;
 mod vars;
 // 1e9e2348 ends here
 
@@ -198,10 +217,25 @@ pub mod prelude {
 // 4ccc7fd1 ends here
 
 // [[file:../spdkit.note::*exports][exports:1]]
+pub use crate::annealing::SimulatedAnnealing;
+pub use crate::basin_hopping::BasinHopping;
+pub use crate::cma_es::CmaEs;
+pub use crate::differential_evolution::DifferentialEvolution;
 pub use crate::engine::{Engine, EvolutionAlgorithm};
+pub use crate::gears::AdaptiveBreeder;
 pub use crate::gears::GeneticBreeder;
+pub use crate::gears::PortfolioBreeder;
 pub use crate::gears::Survivor;
+pub use crate::gears::DiversitySurvivor;
 pub use crate::gears::Valuer;
+pub use crate::genealogy::Genealogy;
+pub use crate::hall_of_fame::HallOfFame;
 pub use crate::individual::{Genome, Individual};
+pub use crate::map_elites::MapElites;
+pub use crate::parallel_tempering::ParallelTempering;
+pub use crate::particle_swarm::ParticleSwarm;
 pub use crate::population::Population;
+pub use crate::report::Report;
+pub use crate::tabu_search::TabuSearch;
+pub use crate::units::{EnergyUnit, Temperature};
 // exports:1 ends here
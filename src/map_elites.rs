@@ -0,0 +1,310 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use std::collections::{BTreeMap, HashMap};
+
+use crate::common::*;
+use crate::engine::Evolve;
+use crate::fitness::*;
+use crate::gears::{Breed, Valuer};
+use crate::individual::*;
+use crate::population::*;
+use crate::random::*;
+use crate::report::Report;
+// imports:1 ends here
+
+// [[file:../spdkit.note::e4a1c7b2][e4a1c7b2]]
+/// Map a genome to its behavior descriptors, e.g. the asphericity and the size
+/// of a cluster.
+///
+/// Any closure in the form of `Fn(&G) -> Vec<f64>` can be used as a
+/// descriptor.
+pub trait Describe<G> {
+    fn describe(&self, genome: &G) -> Vec<f64>;
+}
+
+impl<G, T> Describe<G> for T
+where
+    T: Fn(&G) -> Vec<f64>,
+{
+    fn describe(&self, genome: &G) -> Vec<f64> {
+        self(genome)
+    }
+}
+
+/// A regular grid dividing descriptor space into cells.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Grid {
+    lower: Vec<f64>,
+    upper: Vec<f64>,
+    bins: Vec<usize>,
+}
+
+impl Grid {
+    /// Construct a grid over [lower, upper] with `bins` cells in each
+    /// dimension. Descriptors outside the range are put into the boundary
+    /// cells.
+    pub fn new(lower: Vec<f64>, upper: Vec<f64>, bins: Vec<usize>) -> Self {
+        assert_eq!(lower.len(), upper.len(), "bounds differ in length!");
+        assert_eq!(lower.len(), bins.len(), "bins differ in length!");
+        assert!(lower.iter().zip(&upper).all(|(l, u)| l < u), "invalid bounds");
+        assert!(bins.iter().all(|&n| n > 0), "invalid bins: {bins:?}");
+        Self { lower, upper, bins }
+    }
+
+    /// The total number of cells.
+    pub fn ncells(&self) -> usize {
+        self.bins.iter().product()
+    }
+
+    /// Return the index of cell containing `descriptor`.
+    pub fn cell(&self, descriptor: &[f64]) -> Vec<usize> {
+        assert_eq!(descriptor.len(), self.bins.len(), "invalid descriptor: {descriptor:?}");
+        descriptor
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| {
+                let (l, u, n) = (self.lower[i], self.upper[i], self.bins[i]);
+                let k = ((x - l) / (u - l) * n as f64).floor();
+                (k.max(0.0) as usize).min(n - 1)
+            })
+            .collect()
+    }
+}
+
+/// An elite individual occupying a cell in MAP-Elites archive.
+#[derive(Debug, Clone)]
+pub struct Elite<G: Genome> {
+    pub individual: Individual<G>,
+    pub descriptor: Vec<f64>,
+}
+
+/// MAP-Elites quality-diversity search.
+///
+/// An archive keeps the best individual found in each cell of descriptor
+/// space. In each generation, parents are selected from the elites in
+/// occupied cells by `breeder`, and each child replaces the elite of its cell
+/// if it is fitter, or occupies the cell if it is empty. The population in
+/// each generation consists of all elites.
+///
+/// Archive statistics are reported as "qd/coverage" (the fraction of
+/// occupied cells) and "qd/score". The QD-score is the sum of objective
+/// values of elites shifted by a fixed offset, as `objective - offset` when
+/// maximizing or `offset - objective` when minimizing. With an offset
+/// bounding all objective values, every elite contributes non-negatively, and
+/// the QD-score never decreases during evolution, which makes it comparable
+/// between generations.
+///
+/// # Reference
+///
+/// * Mouret and Clune 2015, Illuminating search spaces by mapping elites, arXiv:1504.04909
+///
+#[derive(Debug, Clone)]
+pub struct MapElites<G: Genome, D: Describe<G>, B: Breed<G>> {
+    describer: D,
+    grid: Grid,
+    breeder: B,
+    batch_size: usize,
+    qd_offset: f64,
+    qd_minimize: bool,
+
+    archive: BTreeMap<Vec<usize>, Elite<G>>,
+
+    // statistics of last generation
+    ninserted: usize,
+}
+
+impl<G, D, B> MapElites<G, D, B>
+where
+    G: Genome,
+    D: Describe<G>,
+    B: Breed<G>,
+{
+    /// Construct with behavior `describer`, `grid` of descriptor space, and
+    /// `breeder` for breeding children from elites.
+    pub fn new(describer: D, grid: Grid, breeder: B) -> Self {
+        Self {
+            describer,
+            grid,
+            breeder,
+            batch_size: 32,
+            qd_offset: 0.0,
+            qd_minimize: false,
+            archive: BTreeMap::new(),
+            ninserted: 0,
+        }
+    }
+
+    /// Set the number of children bred in each generation. The default is 32.
+    pub fn batch_size(mut self, n: usize) -> Self {
+        assert!(n > 0, "invalid batch size: {n}");
+        self.batch_size = n;
+        self
+    }
+
+    /// Set the `offset` of objective values in QD-score, which should be a
+    /// lower bound of objective values when maximizing, or an upper bound when
+    /// `minimize` is true. The default is zero for maximizing.
+    pub fn qd_offset(mut self, offset: f64, minimize: bool) -> Self {
+        self.qd_offset = offset;
+        self.qd_minimize = minimize;
+        self
+    }
+
+    /// Return the QD-score of elites in archive.
+    pub fn qd_score(&self) -> f64 {
+        self.archive
+            .values()
+            .map(|e| e.individual.objective_value() - self.qd_offset)
+            .map(|x| if self.qd_minimize { -x } else { x })
+            .sum()
+    }
+
+    /// Return elites in archive keyed by cell index.
+    pub fn archive(&self) -> &BTreeMap<Vec<usize>, Elite<G>> {
+        &self.archive
+    }
+
+    /// Return the fraction of occupied cells.
+    pub fn coverage(&self) -> f64 {
+        self.archive.len() as f64 / self.grid.ncells() as f64
+    }
+}
+
+impl<G, D, B> MapElites<G, D, B>
+where
+    G: Genome,
+    D: Describe<G>,
+    B: Breed<G>,
+{
+    // Insert candidates into archive. Return the number of cells updated.
+    fn insert<F, C>(&mut self, candidates: Vec<Individual<G>>, valuer: &Valuer<G, F, C>) -> usize
+    where
+        F: EvaluateFitness<G>,
+        C: EvaluateObjectiveValue<G>,
+    {
+        // evaluate fitness of elites and candidates together, so they are
+        // comparable
+        let mut indvs: Vec<_> = self.archive.values().map(|e| e.individual.clone()).collect();
+        let nelites = indvs.len();
+        indvs.extend(candidates);
+        let fitness = valuer.evaluate_fitness(&indvs);
+
+        let mut best: HashMap<Vec<usize>, (usize, f64)> = self
+            .archive
+            .keys()
+            .cloned()
+            .zip(fitness.iter().copied().enumerate())
+            .collect();
+        let mut descriptors = HashMap::new();
+        for i in nelites..indvs.len() {
+            let descriptor = self.describer.describe(indvs[i].genome());
+            let cell = self.grid.cell(&descriptor);
+            match best.get(&cell) {
+                Some(&(_, f)) if f >= fitness[i] => {}
+                _ => {
+                    best.insert(cell, (i, fitness[i]));
+                    descriptors.insert(i, descriptor);
+                }
+            }
+        }
+
+        let mut n = 0;
+        for (cell, (i, _)) in best {
+            if i >= nelites {
+                let elite = Elite {
+                    individual: indvs[i].clone(),
+                    descriptor: descriptors.remove(&i).unwrap(),
+                };
+                self.archive.insert(cell, elite);
+                n += 1;
+            }
+        }
+        n
+    }
+}
+
+impl<G, D, B, F, C> Evolve<G, F, C> for MapElites<G, D, B>
+where
+    G: Genome,
+    D: Describe<G>,
+    B: Breed<G>,
+    F: EvaluateFitness<G>,
+    C: EvaluateObjectiveValue<G>,
+{
+    fn next_generation(&mut self, cur_population: &Population<G>, valuer: &mut Valuer<G, F, C>) -> Population<G> {
+        if self.archive.is_empty() {
+            self.insert(cur_population.individuals().to_vec(), valuer);
+        }
+
+        // 1. breed children from elites
+        let elites: Vec<_> = self.archive.values().map(|e| e.individual.clone()).collect();
+        let parents = valuer.build_population(elites);
+        let genomes = {
+            let mut rng = get_rng!();
+            self.breeder.breed(self.batch_size, &parents, &mut *rng)
+        };
+
+        // 2. place children into archive
+        let children = valuer.create_individuals(genomes);
+        self.ninserted = self.insert(children, valuer);
+
+        let elites: Vec<_> = self.archive.values().map(|e| e.individual.clone()).collect();
+        let n = elites.len();
+        valuer.build_population(elites).with_size_limit(n)
+    }
+
+    fn report(&self) -> Report {
+        let mut report = self.breeder.report();
+        report.set("qd/coverage", self.coverage());
+        report.set("qd/score", self.qd_score());
+        report.set("qd/occupied", self.archive.len());
+        report.set("qd/inserted", self.ninserted);
+        report
+    }
+}
+// e4a1c7b2 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[test]
+fn test_map_elites() -> Result<()> {
+    use crate::encoding::Binary;
+    use crate::engine::Engine;
+    use crate::gears::GeneticBreeder;
+    use crate::operators::selection::RandomSelection;
+    use crate::operators::variation::OnePointCrossOver;
+
+    let grid = Grid::new(vec![0.0, 0.0], vec![1.0, 1.0], vec![4, 4]);
+    assert_eq!(grid.ncells(), 16);
+    assert_eq!(grid.cell(&[0.3, 1.0]), vec![1, 3]);
+    assert_eq!(grid.cell(&[-0.1, 0.5]), vec![0, 2]);
+
+    // the fractions of ones in the first and the second halves
+    let describe = |g: &Binary| {
+        let n = g.len() / 2;
+        let ones = |s: &[bool]| s.iter().filter(|&&b| b).count() as f64 / n as f64;
+        vec![ones(&g[..n]), ones(&g[n..])]
+    };
+    let breeder = GeneticBreeder::new()
+        .with_crossover(OnePointCrossOver)
+        .with_selector(RandomSelection::new(2))
+        .mutation_probability(0.5);
+    let algo = MapElites::new(describe, grid, breeder).batch_size(16);
+    let valuer = Valuer::new().with_fitness(Maximize).with_creator(OneMax);
+    let seeds = vec![Binary::from_str("0000000000"), Binary::from_str("1111111111")];
+    let mut engine = Engine::create().valuer(valuer).algorithm(algo).termination_nlast(100);
+    let mut coverage = 0.0;
+    let mut score = 0.0;
+    for g in engine.evolve(&seeds).skip(1).take(20) {
+        let generation = g?;
+        let c = generation.report.get("qd/coverage").and_then(|x| x.as_number()).unwrap();
+        assert!(c >= coverage && c <= 1.0);
+        coverage = c;
+        let s = generation.report.get("qd/score").and_then(|x| x.as_number()).unwrap();
+        assert!(s >= score, "QD-score decreased: {s} < {score}");
+        score = s;
+        assert_eq!(generation.report.get("qd/occupied").and_then(|x| x.as_count()), Some(generation.population.size()));
+    }
+    assert!(coverage > 2.0 / 16.0);
+
+    Ok(())
+}
+// test:1 ends here
@@ -0,0 +1,200 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use crate::common::*;
+use crate::distance::{pairwise_distances, GenomeDistance};
+use crate::fitness::*;
+use crate::individual::*;
+use crate::population::Population;
+// imports:1 ends here
+
+// [[file:../spdkit.note::3b8e6f15][3b8e6f15]]
+// Pairwise distances between genomes of individuals.
+fn individual_distances<G, D>(indvs: &[Individual<G>], metric: &D) -> Vec<Vec<f64>>
+where
+    G: Genome + Sync,
+    D: GenomeDistance<G> + Sync,
+{
+    let genomes: Vec<_> = indvs.iter().map(|x| x.genome()).collect();
+    pairwise_distances(&genomes, metric)
+}
+// 3b8e6f15 ends here
+
+// [[file:../spdkit.note::*fitness sharing][fitness sharing:1]]
+/// Niching by fitness sharing: the fitness evaluated by an inner fitness
+/// function is divided by the niche count of individual, so that individuals
+/// in crowded region of genome space are penalized.
+///
+/// # Reference
+///
+/// * Goldberg and Richardson 1987
+///
+#[derive(Clone, Debug)]
+pub struct FitnessSharing<F, D> {
+    fitness: F,
+    metric: D,
+    radius: f64,
+    alpha: f64,
+}
+
+impl<F, D> FitnessSharing<F, D> {
+    /// Construct with inner `fitness` function, genome distance `metric` and
+    /// niche `radius`.
+    pub fn new(fitness: F, metric: D, radius: f64) -> Self {
+        assert!(radius > 0.0, "invalid niche radius: {radius}");
+        Self {
+            fitness,
+            metric,
+            radius,
+            alpha: 1.0,
+        }
+    }
+
+    /// Set the exponent `alpha` of sharing function. The default is 1.0
+    /// (triangular sharing).
+    pub fn alpha(mut self, alpha: f64) -> Self {
+        assert!(alpha > 0.0, "invalid alpha: {alpha}");
+        self.alpha = alpha;
+        self
+    }
+}
+
+impl<F, D, G> EvaluateFitness<G> for FitnessSharing<F, D>
+where
+    G: Genome + Sync,
+    F: EvaluateFitness<G>,
+    D: GenomeDistance<G> + Clone + Sync,
+{
+    fn evaluate(&mut self, indvs: &[Individual<G>]) -> Vec<f64> {
+        let values = self.fitness.evaluate(indvs);
+        let dm = individual_distances(indvs, &self.metric);
+
+        values
+            .into_iter()
+            .zip(dm)
+            .map(|(f, distances)| {
+                // always no less than 1 for the self term
+                let niche_count: f64 = distances
+                    .into_iter()
+                    .filter(|&d| d < self.radius)
+                    .map(|d| 1.0 - (d / self.radius).powf(self.alpha))
+                    .sum();
+                f / niche_count
+            })
+            .collect()
+    }
+
+    fn update(&mut self, population: &Population<G>) {
+        self.fitness.update(population);
+    }
+}
+// fitness sharing:1 ends here
+
+// [[file:../spdkit.note::*clearing][clearing:1]]
+/// Niching by clearing: within each niche of `radius`, only the best
+/// `capacity` individuals (the winners) keep their fitness, and the fitness of
+/// the others is cleared to zero.
+///
+/// # Reference
+///
+/// * Petrowski 1996
+///
+#[derive(Clone, Debug)]
+pub struct Clearing<F, D> {
+    fitness: F,
+    metric: D,
+    radius: f64,
+    capacity: usize,
+}
+
+impl<F, D> Clearing<F, D> {
+    /// Construct with inner `fitness` function, genome distance `metric` and
+    /// niche `radius`. The niche capacity is 1 by default.
+    pub fn new(fitness: F, metric: D, radius: f64) -> Self {
+        assert!(radius > 0.0, "invalid niche radius: {radius}");
+        Self {
+            fitness,
+            metric,
+            radius,
+            capacity: 1,
+        }
+    }
+
+    /// Set the max number of winners in each niche.
+    pub fn capacity(mut self, n: usize) -> Self {
+        assert!(n > 0, "invalid niche capacity: {n}");
+        self.capacity = n;
+        self
+    }
+}
+
+impl<F, D, G> EvaluateFitness<G> for Clearing<F, D>
+where
+    G: Genome + Sync,
+    F: EvaluateFitness<G>,
+    D: GenomeDistance<G> + Clone + Sync,
+{
+    fn evaluate(&mut self, indvs: &[Individual<G>]) -> Vec<f64> {
+        let mut values = self.fitness.evaluate(indvs);
+        let dm = individual_distances(indvs, &self.metric);
+
+        let n = indvs.len();
+        let mut order: Vec<_> = (0..n).collect();
+        order.sort_by(|&i, &j| float_ordering_maximize(&values[i], &values[j]));
+
+        let mut cleared = vec![false; n];
+        for (k, &i) in order.iter().enumerate() {
+            if cleared[i] {
+                continue;
+            }
+            let mut nwinners = 1;
+            for &j in &order[k + 1..] {
+                if !cleared[j] && dm[i][j] < self.radius {
+                    if nwinners < self.capacity {
+                        nwinners += 1;
+                    } else {
+                        cleared[j] = true;
+                        values[j] = 0.0;
+                    }
+                }
+            }
+        }
+
+        values
+    }
+
+    fn update(&mut self, population: &Population<G>) {
+        self.fitness.update(population);
+    }
+}
+// clearing:1 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::distance::Hamming;
+    use crate::encoding::Binary;
+
+    #[test]
+    fn test_niching() {
+        let genomes: Vec<_> = ["11110", "11111", "00001"].iter().map(|x| Binary::from_str(x)).collect();
+        let indvs = OneMax.create(genomes);
+        let i = indvs.iter().position(|x| x.genome().to_string() == "11110").unwrap();
+        let j = indvs.iter().position(|x| x.genome().to_string() == "11111").unwrap();
+        let k = indvs.iter().position(|x| x.genome().to_string() == "00001").unwrap();
+
+        let values = Maximize.evaluate(&indvs);
+        let shared = FitnessSharing::new(Maximize, Hamming, 2.0).evaluate(&indvs);
+        assert_eq!(shared[k], values[k]);
+        assert!(shared[i] < values[i]);
+        assert!(shared[j] < values[j]);
+
+        let cleared = Clearing::new(Maximize, Hamming, 2.0).evaluate(&indvs);
+        assert_eq!(cleared[i], 0.0);
+        assert_eq!(cleared[j], values[j]);
+        assert_eq!(cleared[k], values[k]);
+
+        let cleared = Clearing::new(Maximize, Hamming, 2.0).capacity(2).evaluate(&indvs);
+        assert_eq!(cleared, values);
+    }
+}
+// test:1 ends here
@@ -0,0 +1,237 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use std::collections::VecDeque;
+
+use crate::common::*;
+use crate::distance::GenomeDistance;
+use crate::fitness::*;
+use crate::individual::*;
+use crate::map_elites::Describe;
+use crate::population::Population;
+// imports:1 ends here
+
+// [[file:../spdkit.note::a83f52d0][a83f52d0]]
+/// Novelty search: the fitness of individual is its behavioral novelty,
+/// measured as the average distance to its `k` nearest neighbors among the
+/// behaviors of the other individuals and those kept in an archive.
+///
+/// Behaviors are computed by a pluggable descriptor, and compared using a
+/// distance `metric` on behavior descriptors. The novelty can be blended with
+/// the fitness evaluated by an inner fitness function for the objective.
+///
+/// The archive is updated once per generation from the new population (see
+/// `EvaluateFitness::update`), and left untouched by fitness evaluation. When
+/// no archive threshold is set, the most novel individual is archived. The
+/// archived behavior of an individual is not counted as its own neighbor.
+///
+/// # Reference
+///
+/// * Lehman and Stanley 2011, Evol. Comput., 19, 189
+///
+#[derive(Clone, Debug)]
+pub struct Novelty<F, D, M> {
+    fitness: F,
+    describer: D,
+    metric: M,
+    k: usize,
+    weight: f64,
+    threshold: Option<f64>,
+    capacity: usize,
+    // archived behaviors with ids of individuals
+    archive: VecDeque<(usize, Vec<f64>)>,
+}
+
+impl<F, D, M> Novelty<F, D, M> {
+    /// Construct with inner objective `fitness` function, behavior
+    /// `describer`, and distance `metric` between behavior descriptors.
+    pub fn new(fitness: F, describer: D, metric: M) -> Self {
+        Self {
+            fitness,
+            describer,
+            metric,
+            k: 15,
+            weight: 0.0,
+            threshold: None,
+            capacity: 1000,
+            archive: VecDeque::new(),
+        }
+    }
+
+    /// Set the number of nearest neighbors for novelty. The default is 15.
+    pub fn nearest_neighbors(mut self, k: usize) -> Self {
+        assert!(k > 0, "invalid number of nearest neighbors: {k}");
+        self.k = k;
+        self
+    }
+
+    /// Blend novelty with objective fitness as (1 - w) * novelty + w *
+    /// fitness, both scaled by their max values. The default is 0 (pure
+    /// novelty).
+    pub fn blend(mut self, w: f64) -> Self {
+        assert!((0.0..=1.0).contains(&w), "invalid blend weight: {w}");
+        self.weight = w;
+        self
+    }
+
+    /// Archive behaviors with novelty larger than `threshold`.
+    pub fn archive_threshold(mut self, threshold: f64) -> Self {
+        assert!(threshold.is_sign_positive(), "invalid archive threshold: {threshold}");
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Set the max number of behaviors in archive. The oldest ones are
+    /// dropped when it is full. The default is 1000.
+    pub fn archive_capacity(mut self, n: usize) -> Self {
+        self.capacity = n;
+        self
+    }
+
+    /// Return archived behaviors from the oldest to the newest.
+    pub fn archive(&self) -> impl Iterator<Item = &Vec<f64>> {
+        self.archive.iter().map(|(_, b)| b)
+    }
+}
+
+impl<F, D, M> Novelty<F, D, M>
+where
+    M: GenomeDistance<Vec<f64>>,
+{
+    // Average distance to k nearest neighbors among population and archive,
+    // excluding the individual itself.
+    fn novelty(&self, ids: &[usize], behaviors: &[Vec<f64>]) -> Vec<f64> {
+        behaviors
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let archived = self.archive.iter().filter(|(id, _)| *id != ids[i]).map(|(_, b)| b);
+                let mut distances: Vec<_> = behaviors
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, b)| b)
+                    .chain(archived)
+                    .map(|b| self.metric.distance(a, b))
+                    .collect();
+                if distances.is_empty() {
+                    return 0.0;
+                }
+                distances.sort_by(float_ordering_minimize);
+                let k = self.k.min(distances.len());
+                distances[..k].iter().sum::<f64>() / k as f64
+            })
+            .collect()
+    }
+
+    fn update_archive(&mut self, ids: &[usize], behaviors: Vec<Vec<f64>>, novelty: &[f64]) {
+        // an individual is archived at most once
+        let candidates: Vec<_> = ids
+            .iter()
+            .copied()
+            .zip(behaviors)
+            .zip(novelty.iter().copied())
+            .filter(|((id, _), _)| self.archive.iter().all(|(x, _)| x != id))
+            .collect();
+        match self.threshold {
+            Some(t) => {
+                let novel = candidates.into_iter().filter(|(_, s)| *s > t).map(|(x, _)| x);
+                self.archive.extend(novel);
+            }
+            None => {
+                if let Some((i, _)) = candidates.iter().map(|(_, s)| s).imax() {
+                    self.archive.push_back(candidates[i].0.clone());
+                }
+            }
+        }
+        while self.archive.len() > self.capacity {
+            self.archive.pop_front();
+        }
+    }
+}
+
+// Scale values by their max.
+fn scaled(values: &[f64]) -> Vec<f64> {
+    match values.iter().fmax() {
+        Some(m) if m > 0.0 => values.iter().map(|x| x / m).collect(),
+        _ => vec![0.0; values.len()],
+    }
+}
+
+impl<F, D, M, G> EvaluateFitness<G> for Novelty<F, D, M>
+where
+    G: Genome,
+    F: EvaluateFitness<G>,
+    D: Describe<G> + Clone,
+    M: GenomeDistance<Vec<f64>> + Clone,
+{
+    fn evaluate(&mut self, indvs: &[Individual<G>]) -> Vec<f64> {
+        let ids: Vec<_> = indvs.iter().map(|x| x.id()).collect();
+        let behaviors: Vec<_> = indvs.iter().map(|x| self.describer.describe(x.genome())).collect();
+        let novelty = self.novelty(&ids, &behaviors);
+
+        if self.weight > 0.0 {
+            let objective = scaled(&self.fitness.evaluate(indvs));
+            let w = self.weight;
+            scaled(&novelty)
+                .into_iter()
+                .zip(objective)
+                .map(|(n, f)| (1.0 - w) * n + w * f)
+                .collect()
+        } else {
+            novelty
+        }
+    }
+
+    fn update(&mut self, population: &Population<G>) {
+        self.fitness.update(population);
+        let indvs = population.individuals();
+        let ids: Vec<_> = indvs.iter().map(|x| x.id()).collect();
+        let behaviors: Vec<_> = indvs.iter().map(|x| self.describer.describe(x.genome())).collect();
+        let novelty = self.novelty(&ids, &behaviors);
+        self.update_archive(&ids, behaviors, &novelty);
+    }
+}
+// a83f52d0 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::encoding::Binary;
+
+    #[test]
+    fn test_novelty() {
+        let genomes: Vec<_> = ["11110", "11111", "00001"].iter().map(|x| Binary::from_str(x)).collect();
+        let indvs = OneMax.create(genomes);
+        let i = indvs.iter().position(|x| x.genome().to_string() == "11111").unwrap();
+        let k = indvs.iter().position(|x| x.genome().to_string() == "00001").unwrap();
+
+        let describe = |g: &Binary| vec![g.iter().filter(|&&b| b).count() as f64];
+        let metric = |a: &Vec<f64>, b: &Vec<f64>| (a[0] - b[0]).abs();
+        let mut novelty = Novelty::new(Maximize, describe, metric).nearest_neighbors(1);
+        let values = novelty.evaluate(&indvs);
+        assert_eq!(values[k], 3.0);
+        assert_eq!(values[i], 1.0);
+        // evaluation leaves the archive untouched
+        assert_eq!(novelty.archive().count(), 0);
+
+        // the most novel behavior is archived once per generation
+        let population = Population::build(indvs.clone(), &mut Maximize);
+        novelty.update(&population);
+        assert_eq!(novelty.archive().collect::<Vec<_>>(), vec![&vec![1.0]]);
+        novelty.update(&population);
+        assert_eq!(novelty.archive().count(), 2);
+
+        // the archived behavior of the individual itself is not its neighbor
+        let values = novelty.evaluate(&indvs);
+        assert_eq!(values[k], 3.0);
+        // but counts as a neighbor of others
+        let others = OneMax.create(vec![Binary::from_str("00000")]);
+        assert_eq!(novelty.evaluate(&others), vec![1.0]);
+
+        // the fittest individual wins with full weight on objective
+        let mut blended = Novelty::new(Maximize, describe, metric).blend(1.0);
+        let values = blended.evaluate(&indvs);
+        assert_eq!(values.iter().imax().unwrap().0, i);
+    }
+}
+// test:1 ends here
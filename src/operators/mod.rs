@@ -36,6 +36,17 @@ pub trait ReplacementOperator: GeneticOperator {
     fn remove_from<G: Genome, R: Rng + Sized>(&self, n: usize, population: &mut Population<G>, rng: &mut R);
 }
 
+/// For repairing invalid genomes produced by variation.
+pub trait Repair<G>: GeneticOperator
+where
+    G: Genome,
+{
+    /// Repair `genome` in place. Return false if `genome` cannot be repaired
+    /// and should be rejected.
+    fn repair<R: Rng + Sized>(&self, genome: &mut G, rng: &mut R) -> bool;
+}
+
+pub mod repair;
 pub mod replacement;
 pub mod selection;
 pub mod variation;
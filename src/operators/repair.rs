@@ -0,0 +1,175 @@
+// [[file:../../spdkit.note::*imports][imports:1]]
+use std::borrow::BorrowMut;
+
+use gchemol::Molecule;
+
+use crate::common::*;
+use crate::encoding::*;
+use crate::individual::*;
+use crate::random::*;
+
+use super::*;
+// imports:1 ends here
+
+// [[file:../../spdkit.note::*no repair][no repair:1]]
+/// Accept any genome as it is.
+#[derive(Debug, Clone)]
+pub struct NoRepair;
+
+impl<G: Genome> Repair<G> for NoRepair {
+    fn repair<R: Rng + Sized>(&self, _genome: &mut G, _rng: &mut R) -> bool {
+        true
+    }
+}
+// no repair:1 ends here
+
+// [[file:../../spdkit.note::*fixed ones][fixed ones:1]]
+/// Keep a fixed number of ones in `Binary` genome, e.g. for a fixed
+/// stoichiometry in site-occupation problems. Surplus ones or zeros are
+/// flipped at random.
+#[derive(Debug, Clone)]
+pub struct FixedOnesRepair {
+    nones: usize,
+}
+
+impl FixedOnesRepair {
+    /// Construct with the required number of ones `n`.
+    pub fn new(n: usize) -> Self {
+        Self { nones: n }
+    }
+}
+
+impl Repair<Binary> for FixedOnesRepair {
+    fn repair<R: Rng + Sized>(&self, genome: &mut Binary, rng: &mut R) -> bool {
+        if genome.len() < self.nones {
+            return false;
+        }
+
+        let ones: Vec<_> = genome.iter().positions(|&b| b).collect();
+        let zeros: Vec<_> = genome.iter().positions(|&b| !b).collect();
+        let positions: Vec<_> = if ones.len() > self.nones {
+            ones.choose_multiple(rng, ones.len() - self.nones).cloned().collect()
+        } else {
+            zeros.choose_multiple(rng, self.nones - ones.len()).cloned().collect()
+        };
+        genome.flip(positions);
+
+        true
+    }
+}
+// fixed ones:1 ends here
+
+// [[file:../../spdkit.note::*minimum distance][minimum distance:1]]
+/// Push apart atoms in molecular genome closer than a minimum distance.
+/// Genome that still has too close atoms after `max_cycles` cycles will be
+/// rejected.
+///
+/// # NOTE
+///
+/// * Periodic images are not taken into account.
+#[derive(Debug, Clone)]
+pub struct MinimumDistanceRepair {
+    distance: f64,
+    max_cycles: usize,
+}
+
+impl MinimumDistanceRepair {
+    /// Construct with minimum interatomic distance `d`.
+    pub fn new(d: f64) -> Self {
+        assert!(d > 0.0, "invalid minimum distance: {d}");
+        Self {
+            distance: d,
+            max_cycles: 50,
+        }
+    }
+
+    /// Set the max number of cycles for pushing atoms apart.
+    pub fn max_cycles(mut self, n: usize) -> Self {
+        self.max_cycles = n;
+        self
+    }
+
+    fn push_apart<R: Rng + Sized>(&self, mol: &mut Molecule, rng: &mut R) -> bool {
+        let mut positions: Vec<_> = mol.positions().collect();
+        let n = positions.len();
+        // one more cycle for checking clashes after the last push
+        for cycle in 0..=self.max_cycles {
+            let mut clashed = false;
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let mut v: Vec<_> = (0..3).map(|k| positions[j][k] - positions[i][k]).collect();
+                    let mut d = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+                    if d + 1e-6 >= self.distance {
+                        continue;
+                    }
+                    clashed = true;
+                    if cycle == self.max_cycles {
+                        return false;
+                    }
+                    // choose a random direction for overlapping atoms
+                    if d < 1e-6 {
+                        v = (0..3).map(|_| rng.gen_range(-1.0..1.0)).collect();
+                        d = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+                    }
+                    // move both atoms by half of the missing distance
+                    let s = 0.5 * (self.distance - d) / d;
+                    for k in 0..3 {
+                        positions[i][k] -= s * v[k];
+                        positions[j][k] += s * v[k];
+                    }
+                }
+            }
+            if !clashed {
+                mol.set_positions(positions);
+                return true;
+            }
+        }
+        unreachable!()
+    }
+}
+
+impl<G> Repair<G> for MinimumDistanceRepair
+where
+    G: Genome + BorrowMut<Molecule>,
+{
+    fn repair<R: Rng + Sized>(&self, genome: &mut G, rng: &mut R) -> bool {
+        self.push_apart(genome.borrow_mut(), rng)
+    }
+}
+// minimum distance:1 ends here
+
+// [[file:../../spdkit.note::*test][test:1]]
+#[test]
+fn test_repair() -> Result<()> {
+    use gchemol::prelude::*;
+    use gchemol::Atom;
+
+    let mut rng = get_rng!();
+
+    let mut g = Binary::from_str("1110111");
+    assert!(FixedOnesRepair::new(3).repair(&mut g, &mut *rng));
+    assert_eq!(g.iter().filter(|&&b| b).count(), 3);
+    let mut g = Binary::from_str("000");
+    assert!(FixedOnesRepair::new(2).repair(&mut g, &mut *rng));
+    assert_eq!(g.iter().filter(|&&b| b).count(), 2);
+    assert!(!FixedOnesRepair::new(4).repair(&mut g, &mut *rng));
+
+    let mut mol = Molecule::from_file("./tests/files/CH4.xyz")?;
+    let repair = MinimumDistanceRepair::new(1.5);
+    assert!(repair.push_apart(&mut mol, &mut *rng));
+    for i in 1..=mol.natoms() {
+        for j in (i + 1)..=mol.natoms() {
+            assert!(mol.get_distance(i, j).unwrap() >= 1.5 - 1e-6);
+        }
+    }
+
+    // a clash resolved in the last allowed cycle is accepted
+    let mut mol = Molecule::from_atoms(vec![Atom::new("H", [0.0, 0.0, 0.0]), Atom::new("H", [0.5, 0.0, 0.0])]);
+    assert!(MinimumDistanceRepair::new(1.0).max_cycles(1).push_apart(&mut mol, &mut *rng));
+    assert!(mol.get_distance(1, 2).unwrap() >= 1.0 - 1e-6);
+    let mut mol = Molecule::from_atoms(vec![Atom::new("H", [0.0, 0.0, 0.0]), Atom::new("H", [0.5, 0.0, 0.0])]);
+    assert!(!MinimumDistanceRepair::new(1.0).max_cycles(0).push_apart(&mut mol, &mut *rng));
+
+    Ok(())
+}
+// test:1 ends here
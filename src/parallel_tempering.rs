@@ -0,0 +1,264 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use std::collections::{HashMap, HashSet};
+
+use crate::annealing::metropolis;
+use crate::common::*;
+use crate::encoding::Mutate;
+use crate::engine::Evolve;
+use crate::fitness::*;
+use crate::gears::Valuer;
+use crate::individual::*;
+use crate::population::*;
+use crate::random::*;
+use crate::report::Report;
+use crate::units::{EnergyUnit, Temperature};
+// imports:1 ends here
+
+// [[file:../spdkit.note::c7d2a85e][c7d2a85e]]
+/// Parallel tempering (replica-exchange Monte Carlo) over genomes with
+/// `Mutate` implementation.
+///
+/// One Metropolis walker is run at each temperature of a ladder. Trial moves
+/// of all walkers are evaluated in parallel. After the Monte Carlo steps in
+/// each generation, swaps of configurations between neighboring temperatures
+/// are attempted, alternately for even and odd pairs.
+///
+/// The objective value of individual is treated as energy to be minimized,
+/// unless `maximize` is set. The population in each generation consists of
+/// the walkers, ordered from the lowest temperature to the highest.
+///
+/// # Reference
+///
+/// * Swendsen and Wang 1986; Earl and Deem 2005, Phys. Chem. Chem. Phys., 7, 3910
+///
+#[derive(Debug, Clone)]
+pub struct ParallelTempering<G: Genome> {
+    temperatures: Vec<Temperature>,
+    unit: EnergyUnit,
+    maximize: bool,
+    nsteps: usize,
+    mutation_size: usize,
+
+    walkers: Vec<Individual<G>>,
+    // the number of accepted moves and trials for each walker
+    naccepted: Vec<usize>,
+    ntrials: usize,
+    // the number of accepted swaps and attempts for each neighboring pair
+    nswapped: Vec<usize>,
+    nswaps: Vec<usize>,
+    // alternate between even and odd pairs
+    odd: bool,
+}
+
+impl<G: Genome> ParallelTempering<G> {
+    /// Construct with a ladder of `temperatures` in ascending order.
+    pub fn new(temperatures: Vec<Temperature>) -> Self {
+        assert!(temperatures.len() >= 2, "at least two temperatures required.");
+        assert!(
+            temperatures.windows(2).all(|w| w[0] < w[1]),
+            "temperatures should be in ascending order."
+        );
+        let n = temperatures.len();
+        Self {
+            temperatures,
+            unit: EnergyUnit::default(),
+            maximize: false,
+            nsteps: 10,
+            mutation_size: 1,
+            walkers: vec![],
+            naccepted: vec![0; n],
+            ntrials: 0,
+            nswapped: vec![0; n - 1],
+            nswaps: vec![0; n - 1],
+            odd: false,
+        }
+    }
+
+    /// Construct with `n` temperatures in geometric progression from `tl` to
+    /// `th`.
+    pub fn geometric(tl: impl Into<Temperature>, th: impl Into<Temperature>, n: usize) -> Self {
+        assert!(n >= 2, "at least two temperatures required.");
+        let tl = tl.into().to_kelvin();
+        let th = th.into().to_kelvin();
+        let r = (th / tl).powf(1.0 / (n - 1) as f64);
+        let temperatures = (0..n).map(|i| Temperature::kelvin(tl * r.powi(i as i32))).collect();
+        Self::new(temperatures)
+    }
+
+    /// Set energy unit of individual objective value.
+    pub fn energy_unit(mut self, u: EnergyUnit) -> Self {
+        self.unit = u;
+        self
+    }
+
+    /// Maximize objective value instead of minimizing it.
+    pub fn maximize(mut self) -> Self {
+        self.maximize = true;
+        self
+    }
+
+    /// Set the number of Monte Carlo steps of each walker between swap
+    /// attempts. The default is 10.
+    pub fn steps_per_generation(mut self, n: usize) -> Self {
+        assert!(n > 0, "invalid number of steps: {n}");
+        self.nsteps = n;
+        self
+    }
+
+    /// Set the number of bits/points to mutate in each move. The default is 1.
+    pub fn mutation_size(mut self, n: usize) -> Self {
+        assert!(n > 0, "invalid mutation size: {n}");
+        self.mutation_size = n;
+        self
+    }
+
+    /// Return the temperature ladder.
+    pub fn temperatures(&self) -> &[Temperature] {
+        &self.temperatures
+    }
+
+    /// Return current walkers from the lowest temperature to the highest.
+    pub fn walkers(&self) -> &[Individual<G>] {
+        &self.walkers
+    }
+
+    /// Return the acceptance rates of Monte Carlo moves of each walker.
+    pub fn acceptance_rates(&self) -> Vec<f64> {
+        self.naccepted
+            .iter()
+            .map(|&n| if self.ntrials > 0 { n as f64 / self.ntrials as f64 } else { 0.0 })
+            .collect()
+    }
+
+    /// Return the acceptance rates of swaps between temperature `i` and
+    /// `i+1`.
+    pub fn swap_rates(&self) -> Vec<f64> {
+        self.nswapped
+            .iter()
+            .zip(&self.nswaps)
+            .map(|(&a, &n)| if n > 0 { a as f64 / n as f64 } else { 0.0 })
+            .collect()
+    }
+}
+
+impl<G: Genome> ParallelTempering<G> {
+    fn energy(&self, indv: &Individual<G>) -> f64 {
+        if self.maximize {
+            -indv.objective_value()
+        } else {
+            indv.objective_value()
+        }
+    }
+
+    fn is_synchronized(&self, population: &Population<G>) -> bool {
+        let current: HashSet<_> = self.walkers.iter().map(|x| x.genome()).collect();
+        !self.walkers.is_empty() && population.individuals().iter().all(|x| current.contains(x.genome()))
+    }
+
+    // Place individuals in population onto the ladder, the lower the energy,
+    // the lower the temperature.
+    fn initialize(&mut self, population: &Population<G>) {
+        let mut indvs = population.individuals().to_vec();
+        assert!(!indvs.is_empty(), "empty population");
+        indvs.sort_by(|a, b| float_ordering_minimize(&self.energy(a), &self.energy(b)));
+        self.walkers = indvs.into_iter().cycle().take(self.temperatures.len()).collect();
+    }
+
+    // Attempt swaps between neighboring temperatures.
+    fn swap<R: Rng + Sized>(&mut self, rng: &mut R) {
+        let start = if self.odd { 1 } else { 0 };
+        self.odd = !self.odd;
+        for i in (start..self.temperatures.len() - 1).step_by(2) {
+            let bi = 1.0 / self.temperatures[i].kt(self.unit);
+            let bj = 1.0 / self.temperatures[i + 1].kt(self.unit);
+            let ei = self.energy(&self.walkers[i]);
+            let ej = self.energy(&self.walkers[i + 1]);
+            let p = ((bi - bj) * (ei - ej)).exp();
+            self.nswaps[i] += 1;
+            if p >= 1.0 || rng.gen::<f64>() < p {
+                self.walkers.swap(i, i + 1);
+                self.nswapped[i] += 1;
+            }
+        }
+    }
+}
+
+impl<G, F, C> Evolve<G, F, C> for ParallelTempering<G>
+where
+    G: Genome + Mutate,
+    F: EvaluateFitness<G>,
+    C: EvaluateObjectiveValue<G>,
+{
+    fn next_generation(&mut self, cur_population: &Population<G>, valuer: &mut Valuer<G, F, C>) -> Population<G> {
+        if !self.is_synchronized(cur_population) {
+            self.initialize(cur_population);
+        }
+
+        let mut rng = get_rng!();
+        for _ in 0..self.nsteps {
+            // create trial moves for all walkers, and evaluate them in parallel
+            let trials: Vec<_> = self
+                .walkers
+                .iter()
+                .map(|w| {
+                    let mut g = w.genome().clone();
+                    g.mutate(self.mutation_size, &mut *rng);
+                    g
+                })
+                .collect();
+            let created: HashMap<_, _> = valuer
+                .create_individuals(trials.clone())
+                .into_iter()
+                .map(|x| (x.genome().to_owned(), x))
+                .collect();
+
+            for (i, g) in trials.iter().enumerate() {
+                let trial = &created[g];
+                let delta = self.energy(trial) - self.energy(&self.walkers[i]);
+                if metropolis(delta, self.temperatures[i], self.unit, &mut *rng) {
+                    self.walkers[i] = trial.clone();
+                    self.naccepted[i] += 1;
+                }
+            }
+            self.ntrials += 1;
+        }
+        self.swap(&mut *rng);
+
+        let n = self.walkers.len();
+        valuer.build_population(self.walkers.clone()).with_size_limit(n)
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        report.set("pt/acceptance_rates", self.acceptance_rates());
+        report.set("pt/swap_rates", self.swap_rates());
+        report
+    }
+}
+// c7d2a85e ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[test]
+fn test_parallel_tempering() -> Result<()> {
+    use crate::encoding::Binary;
+    use crate::engine::Engine;
+    use vecfx::approx::assert_relative_eq;
+
+    let kt = |x| Temperature::from_kt(x, EnergyUnit::ElectronVolt);
+    let valuer = Valuer::new().with_fitness(Maximize).with_creator(OneMax);
+    let algo = ParallelTempering::geometric(kt(0.1), kt(2.0), 4).maximize();
+    assert_relative_eq!(algo.temperatures()[3].kt(EnergyUnit::ElectronVolt), 2.0, epsilon = 1e-8);
+
+    let seeds = vec![Binary::from_str("0000000000"), Binary::from_str("1100000000")];
+    let mut engine = Engine::create().valuer(valuer).algorithm(algo).termination_nlast(100);
+    for g in engine.evolve(&seeds).skip(1).take(10) {
+        let generation = g?;
+        assert_eq!(generation.population.size(), 4);
+        let rates = generation.report.get("pt/swap_rates").and_then(|x| x.as_numbers()).unwrap();
+        assert_eq!(rates.len(), 3);
+        assert!(rates.iter().all(|r| (0.0..=1.0).contains(r)));
+    }
+
+    Ok(())
+}
+// test:1 ends here
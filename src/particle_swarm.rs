@@ -0,0 +1,293 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use std::collections::{HashMap, HashSet};
+
+use crate::common::*;
+use crate::encoding::{Bounds, RealVector};
+use crate::engine::Evolve;
+use crate::fitness::*;
+use crate::gears::Valuer;
+use crate::individual::*;
+use crate::population::*;
+use crate::random::*;
+use crate::report::Report;
+// imports:1 ends here
+
+// [[file:../spdkit.note::0e5b7c19][0e5b7c19]]
+/// Neighborhood topologies of particle swarm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Topology {
+    /// All particles are connected (gbest).
+    #[default]
+    Global,
+    /// Each particle is connected to its two adjacent particles (lbest).
+    Ring,
+    /// Particles are placed on a toroidal lattice with about sqrt(n)
+    /// columns, and each particle is connected to its four neighbors.
+    VonNeumann,
+}
+
+impl Topology {
+    // Return indices of particles in the neighborhood of particle `i`,
+    // including itself.
+    fn neighbors(&self, i: usize, n: usize) -> Vec<usize> {
+        match self {
+            Topology::Global => (0..n).collect(),
+            Topology::Ring => vec![(i + n - 1) % n, i, (i + 1) % n],
+            Topology::VonNeumann => {
+                let ncols = ((n as f64).sqrt().round() as usize).max(1);
+                vec![
+                    i,
+                    (i + n - 1) % n,
+                    (i + 1) % n,
+                    (i + n - ncols % n) % n,
+                    (i + ncols) % n,
+                ]
+            }
+        }
+    }
+}
+
+/// Velocity update rules of particle swarm.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VelocityUpdate {
+    /// v = chi (v + phi1 r1 (p - x) + phi2 r2 (g - x)), where chi is the
+    /// constriction coefficient derived from phi = phi1 + phi2 > 4 (Clerc and
+    /// Kennedy 2002).
+    Constriction { phi1: f64, phi2: f64 },
+    /// v = w v + c1 r1 (p - x) + c2 r2 (g - x) (Shi and Eberhart 1998).
+    InertiaWeight { w: f64, c1: f64, c2: f64 },
+}
+
+impl Default for VelocityUpdate {
+    fn default() -> Self {
+        VelocityUpdate::Constriction { phi1: 2.05, phi2: 2.05 }
+    }
+}
+
+impl VelocityUpdate {
+    // Return coefficients for (v, p - x, g - x)
+    fn coefficients(&self) -> (f64, f64, f64) {
+        match *self {
+            VelocityUpdate::Constriction { phi1, phi2 } => {
+                let phi = phi1 + phi2;
+                assert!(phi > 4.0, "phi1 + phi2 should be larger than 4: {phi}");
+                let chi = 2.0 / (2.0 - phi - (phi * phi - 4.0 * phi).sqrt()).abs();
+                (chi, chi * phi1, chi * phi2)
+            }
+            VelocityUpdate::InertiaWeight { w, c1, c2 } => (w, c1, c2),
+        }
+    }
+}
+
+/// Particle swarm optimization for continuous optimization on `RealVector`
+/// genomes.
+///
+/// Velocities and personal bests of particles are kept internally, and the
+/// population in each generation consists of current positions of particles.
+/// The swarm is reinitialized from the incoming population when it does not
+/// match current positions, e.g. after a restart in `Engine`.
+///
+/// # Reference
+///
+/// * Kennedy and Eberhart 1995, Particle swarm optimization
+/// * Kennedy and Mendes 2002, Population structure and particle swarm performance
+///
+#[derive(Debug, Clone, Default)]
+pub struct ParticleSwarm {
+    topology: Topology,
+    update: VelocityUpdate,
+    bounds: Option<Bounds>,
+
+    positions: Vec<RealVector>,
+    velocities: Vec<Vec<f64>>,
+    pbests: Vec<Individual<RealVector>>,
+
+    // statistics of last generation
+    nimproved: usize,
+    mean_speed: f64,
+}
+
+impl ParticleSwarm {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set neighborhood topology of swarm.
+    pub fn topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Set velocity update rule.
+    pub fn velocity_update(mut self, update: VelocityUpdate) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Restrict particles within `bounds`. The velocity component is reset to
+    /// zero when a particle hits the boundary.
+    pub fn bounds(mut self, bounds: Bounds) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Return current velocities of particles.
+    pub fn velocities(&self) -> &[Vec<f64>] {
+        &self.velocities
+    }
+
+    /// Return personal best individuals of particles.
+    pub fn personal_bests(&self) -> &[Individual<RealVector>] {
+        &self.pbests
+    }
+}
+
+impl ParticleSwarm {
+    fn initialize<R: Rng + Sized>(&mut self, population: &Population<RealVector>, rng: &mut R) {
+        self.pbests = population.individuals().to_vec();
+        self.positions = self.pbests.iter().map(|x| x.genome().clone()).collect();
+        self.velocities = self
+            .positions
+            .iter()
+            .map(|x| match &self.bounds {
+                Some(b) => b
+                    .lower()
+                    .iter()
+                    .zip(b.upper())
+                    .map(|(l, u)| 0.5 * (u - l) * (2.0 * rng.gen::<f64>() - 1.0))
+                    .collect(),
+                None => vec![0.0; x.len()],
+            })
+            .collect();
+    }
+
+    fn is_synchronized(&self, population: &Population<RealVector>) -> bool {
+        let current: HashSet<_> = self.positions.iter().collect();
+        population.size() == self.positions.len() && population.individuals().iter().all(|x| current.contains(x.genome()))
+    }
+
+    // Move particles with updated velocities.
+    fn fly<R: Rng + Sized>(&mut self, fitness: &[f64], rng: &mut R) {
+        let n = self.positions.len();
+        let (a, b, c) = self.update.coefficients();
+        for i in 0..n {
+            let (ibest, _) = self
+                .topology
+                .neighbors(i, n)
+                .into_iter()
+                .map(|j| (j, fitness[j]))
+                .min_by(|x, y| float_ordering_maximize(&x.1, &y.1))
+                .expect("empty neighborhood");
+            let p = self.pbests[i].genome();
+            let g = self.pbests[ibest].genome();
+            let x = &mut self.positions[i];
+            let v = &mut self.velocities[i];
+            for k in 0..x.len() {
+                let (r1, r2): (f64, f64) = (rng.gen(), rng.gen());
+                v[k] = a * v[k] + b * r1 * (p[k] - x[k]) + c * r2 * (g[k] - x[k]);
+                x[k] += v[k];
+                if let Some(bounds) = &self.bounds {
+                    let (l, u) = (bounds.lower()[k], bounds.upper()[k]);
+                    if x[k] < l || x[k] > u {
+                        x[k] = x[k].clamp(l, u);
+                        v[k] = 0.0;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<F, C> Evolve<RealVector, F, C> for ParticleSwarm
+where
+    F: EvaluateFitness<RealVector>,
+    C: EvaluateObjectiveValue<RealVector>,
+{
+    fn next_generation(
+        &mut self,
+        cur_population: &Population<RealVector>,
+        valuer: &mut Valuer<RealVector, F, C>,
+    ) -> Population<RealVector> {
+        let mut rng = get_rng!();
+        if !self.is_synchronized(cur_population) {
+            self.initialize(cur_population, &mut *rng);
+        }
+        let n = self.positions.len();
+
+        // 1. move particles toward the best in neighborhood
+        let fitness = valuer.evaluate_fitness(&self.pbests);
+        self.fly(&fitness, &mut *rng);
+
+        // 2. evaluate new positions
+        let created: HashMap<_, _> = valuer
+            .create_individuals(self.positions.clone())
+            .into_iter()
+            .map(|x| (x.genome().to_owned(), x))
+            .collect();
+        let indvs: Vec<_> = self.positions.iter().map(|x| created[x].clone()).collect();
+
+        // 3. update personal bests
+        let mut combined = self.pbests.clone();
+        combined.extend_from_slice(&indvs);
+        let fitness = valuer.evaluate_fitness(&combined);
+        self.nimproved = 0;
+        for i in 0..n {
+            if fitness[n + i] > fitness[i] {
+                self.pbests[i] = indvs[i].clone();
+                self.nimproved += 1;
+            }
+        }
+        self.mean_speed = self
+            .velocities
+            .iter()
+            .map(|v| v.iter().map(|x| x * x).sum::<f64>().sqrt())
+            .sum::<f64>()
+            / n as f64;
+
+        valuer.build_population(indvs).with_size_limit(cur_population.size_limit())
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        report.set("pso/pbest_improved", self.nimproved);
+        report.set("pso/mean_speed", self.mean_speed);
+        report
+    }
+}
+// 0e5b7c19 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn test_particle_swarm() -> Result<()> {
+        let bounds = Bounds::uniform(3, -5.0, 5.0);
+        let seeds: Vec<_> = {
+            let mut rng = get_rng!();
+            (0..16).map(|_| bounds.random(&mut *rng)).collect()
+        };
+        let updates = [
+            VelocityUpdate::default(),
+            VelocityUpdate::InertiaWeight { w: 0.729, c1: 1.494, c2: 1.494 },
+        ];
+        for topology in [Topology::Global, Topology::Ring, Topology::VonNeumann] {
+            for update in updates {
+                let algo = ParticleSwarm::new()
+                    .topology(topology)
+                    .velocity_update(update)
+                    .bounds(bounds.clone());
+                let best = minimize_sphere(algo, &seeds, 51, |g| {
+                    assert_eq!(g.population.size(), 16);
+                    assert!(g.report.get("pso/mean_speed").is_some() || g.index == 0);
+                })?;
+                assert!(best < 1e-2, "{topology:?} {update:?}: {best}");
+            }
+        }
+
+        Ok(())
+    }
+}
+// test:1 ends here
@@ -32,6 +32,10 @@ where
         &self.individuals
     }
 
+    pub(crate) fn individuals_mut(&mut self) -> &mut [Individual<G>] {
+        &mut self.individuals
+    }
+
     /// Return population size.
     pub fn size(&self) -> usize {
         self.individuals.len()
@@ -80,6 +84,16 @@ where
         self.size_limit = limit;
         self
     }
+
+    /// Return a sub-population of individuals at `indices`, keeping their
+    /// evaluated fitness values.
+    pub fn subset(&self, indices: &[usize]) -> Self {
+        Self {
+            individuals: indices.iter().map(|&i| self.individuals[i].clone()).collect(),
+            fitness_values: indices.iter().map(|&i| self.fitness_values[i]).collect(),
+            size_limit: indices.len(),
+        }
+    }
 }
 
 /// Evaluate individuals with a fitness function.
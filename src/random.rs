@@ -37,6 +37,22 @@ macro_rules! get_rng {
     };
 }
 
+/// Draw a sample from normal distribution with `mean` and standard deviation
+/// `sd`, using Box-Muller transform.
+pub fn sample_normal<R: Rng + Sized>(rng: &mut R, mean: f64, sd: f64) -> f64 {
+    // avoid ln(0)
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+    mean + sd * z
+}
+
+/// Draw a sample from Cauchy distribution with `location` and `scale`.
+pub fn sample_cauchy<R: Rng + Sized>(rng: &mut R, location: f64, scale: f64) -> f64 {
+    let u: f64 = rng.gen();
+    location + scale * (std::f64::consts::PI * (u - 0.5)).tan()
+}
+
 #[cfg(test)]
 mod test {
     #[test]
@@ -46,6 +62,10 @@ mod test {
         let mut rng = get_rng!();
         rng.gen::<i32>();
         assert_eq!(rng.gen_range(0..1), 0);
+
+        let n = 10000;
+        let mean = (0..n).map(|_| sample_normal(&mut *rng, 1.0, 0.5)).sum::<f64>() / n as f64;
+        assert!((mean - 1.0).abs() < 0.05, "{mean}");
     }
 }
 // 6594d8a9 ends here
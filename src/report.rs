@@ -0,0 +1,153 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use std::collections::BTreeMap;
+
+use crate::common::*;
+// imports:1 ends here
+
+// [[file:../spdkit.note::9e3b51f0][9e3b51f0]]
+/// A value in `Report`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Count(usize),
+    Number(f64),
+    Counts(Vec<usize>),
+    Numbers(Vec<f64>),
+}
+
+impl Value {
+    /// Return the value if it is a count.
+    pub fn as_count(&self) -> Option<usize> {
+        match self {
+            Value::Count(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    /// Return the value as a number. A count is converted into a number.
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Value::Number(x) => Some(*x),
+            Value::Count(x) => Some(*x as f64),
+            _ => None,
+        }
+    }
+
+    /// Return the value if it is a list of counts.
+    pub fn as_counts(&self) -> Option<&[usize]> {
+        match self {
+            Value::Counts(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Return the value if it is a list of numbers.
+    pub fn as_numbers(&self) -> Option<&[f64]> {
+        match self {
+            Value::Numbers(x) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+impl From<usize> for Value {
+    fn from(x: usize) -> Self {
+        Value::Count(x)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Self {
+        Value::Number(x)
+    }
+}
+
+impl From<Vec<usize>> for Value {
+    fn from(x: Vec<usize>) -> Self {
+        Value::Counts(x)
+    }
+}
+
+impl From<Vec<f64>> for Value {
+    fn from(x: Vec<f64>) -> Self {
+        Value::Numbers(x)
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Value::Count(x) => write!(f, "{x}"),
+            Value::Number(x) => write!(f, "{x}"),
+            Value::Counts(x) => write!(f, "{x:?}"),
+            Value::Numbers(x) => write!(f, "{x:?}"),
+        }
+    }
+}
+// 9e3b51f0 ends here
+
+// [[file:../spdkit.note::4a0d7c62][4a0d7c62]]
+/// Named statistics reported by evolution gears and algorithms for a
+/// generation, such as the number of rejected genomes in breeding.
+///
+/// Keys are conventionally prefixed by the reporting component, e.g.
+/// "repair/rejected".
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    values: BTreeMap<String, Value>,
+}
+
+impl Report {
+    /// Set `value` for `key`, replacing any old value.
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<Value>) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    /// Return the value for `key`.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    /// Merge all values from `other` into `self`.
+    pub fn merge(&mut self, other: Report) {
+        self.values.extend(other.values);
+    }
+
+    /// Return true if nothing reported.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Return an iterator over reported keys and values.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (k, v) in self.iter() {
+            writeln!(f, " {k} = {v}")?;
+        }
+        Ok(())
+    }
+}
+// 4a0d7c62 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[test]
+fn test_report() {
+    let mut report = Report::default();
+    assert!(report.is_empty());
+    report.set("repair/rejected", 2usize);
+    report.set("operator/probabilities", vec![0.2, 0.8]);
+
+    let mut other = Report::default();
+    other.set("repair/rejected", 3usize);
+    report.merge(other);
+    assert_eq!(report.get("repair/rejected").and_then(|x| x.as_count()), Some(3));
+    assert_eq!(report.get("repair/rejected").and_then(|x| x.as_number()), Some(3.0));
+    assert_eq!(report.get("operator/probabilities").and_then(|x| x.as_numbers()), Some(&[0.2, 0.8][..]));
+    assert!(report.get("xx").is_none());
+}
+// test:1 ends here
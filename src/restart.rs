@@ -0,0 +1,107 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use crate::encoding::Mutate;
+use crate::fitness::*;
+use crate::gears::Valuer;
+use crate::individual::*;
+use crate::population::*;
+use crate::random::*;
+// imports:1 ends here
+
+// [[file:../spdkit.note::1f6b9d27][1f6b9d27]]
+/// Create new genomes for reseeding a population, e.g. in cataclysmic
+/// restarts or for random immigrants.
+///
+/// Any closure in the form of `FnMut(&mut dyn RngCore) -> G` can be used as a
+/// genome generator.
+pub trait Reseed<G: Genome> {
+    /// Create `n` new genomes for reseeding `population`.
+    fn reseed(&mut self, n: usize, population: &Population<G>, rng: &mut dyn RngCore) -> Vec<G>;
+}
+
+impl<G, T> Reseed<G> for T
+where
+    G: Genome,
+    T: FnMut(&mut dyn RngCore) -> G,
+{
+    fn reseed(&mut self, n: usize, _population: &Population<G>, rng: &mut dyn RngCore) -> Vec<G> {
+        (0..n).map(|_| self(rng)).collect()
+    }
+}
+
+/// Reseed by heavily mutating genomes of randomly chosen members.
+#[derive(Debug, Clone)]
+pub struct HeavyMutation {
+    mutation_size: usize,
+}
+
+impl HeavyMutation {
+    /// Construct with the number of bits/points `n` to be mutated.
+    pub fn new(n: usize) -> Self {
+        Self { mutation_size: n }
+    }
+}
+
+impl<G> Reseed<G> for HeavyMutation
+where
+    G: Genome + Mutate,
+{
+    fn reseed(&mut self, n: usize, population: &Population<G>, mut rng: &mut dyn RngCore) -> Vec<G> {
+        let indvs = population.individuals();
+        (0..n)
+            .filter_map(|_| {
+                let mut g = indvs.choose(&mut rng)?.genome().clone();
+                g.mutate(self.mutation_size, &mut rng);
+                Some(g)
+            })
+            .collect()
+    }
+}
+// 1f6b9d27 ends here
+
+// [[file:../spdkit.note::a93e0c58][a93e0c58]]
+/// Replace all but the best `nkeep` members in `population` with new
+/// individuals created from reseeded genomes.
+pub(crate) fn reseed_population<G, F, C>(
+    population: &Population<G>,
+    nkeep: usize,
+    reseed: &mut dyn Reseed<G>,
+    valuer: &mut Valuer<G, F, C>,
+    rng: &mut dyn RngCore,
+) -> Population<G>
+where
+    G: Genome,
+    F: EvaluateFitness<G>,
+    C: EvaluateObjectiveValue<G>,
+{
+    let nlimit = population.size_limit();
+    let mut members: Vec<_> = population.members().collect();
+    members.sort_by_fitness();
+    let mut indvs: Vec<_> = members.into_iter().take(nkeep).map(|m| m.individual.to_owned()).collect();
+
+    let n = nlimit.saturating_sub(indvs.len());
+    let genomes = reseed.reseed(n, population, rng);
+    indvs.extend(valuer.create_individuals(genomes));
+
+    valuer.build_population(indvs).with_size_limit(nlimit)
+}
+// a93e0c58 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[test]
+fn test_reseed() {
+    use crate::encoding::Binary;
+
+    let mut valuer = Valuer::new().with_fitness(Maximize).with_creator(OneMax);
+    let genomes: Vec<_> = ["11110", "00011", "10101"].iter().map(|x| Binary::from_str(x)).collect();
+    let population = valuer.build_population(valuer.create_individuals(genomes));
+
+    let mut rng = get_rng!();
+    let mut zeros = |_: &mut dyn RngCore| Binary::from_str("00000");
+    let new = reseed_population(&population, 1, &mut zeros, &mut valuer, &mut *rng);
+    assert_eq!(new.size(), 2);
+    assert_eq!(new.best_member().unwrap().genome().to_string(), "11110");
+
+    let genomes = HeavyMutation::new(5).reseed(2, &population, &mut *rng);
+    assert_eq!(genomes.len(), 2);
+}
+// test:1 ends here
@@ -0,0 +1,261 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use std::collections::HashMap;
+
+use crate::common::*;
+use crate::encoding::Binary;
+use crate::engine::Evolve;
+use crate::fitness::*;
+use crate::gears::Valuer;
+use crate::individual::*;
+use crate::population::*;
+use crate::random::*;
+use crate::report::Report;
+// imports:1 ends here
+
+// [[file:../spdkit.note::2f8b6d4c][2f8b6d4c]]
+/// Neighborhood structures of `Binary` genome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Neighborhood {
+    /// Flip one bit.
+    #[default]
+    BitFlip,
+    /// Swap two different bits, keeping the number of ones unchanged, which
+    /// is suitable for site-occupation problems.
+    Swap,
+}
+
+/// Aspiration criteria for overriding tabu status of a move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Aspiration {
+    /// Tabu moves are never allowed.
+    None,
+    /// Allow a tabu move if it leads to a solution better than the best found
+    /// so far.
+    #[default]
+    BestSoFar,
+}
+
+/// Tabu search over `Binary` genomes.
+///
+/// In each generation, the full or a randomly sampled neighborhood of current
+/// solution is evaluated in parallel through `Valuer`, and current solution
+/// moves to the best neighbor that is not tabu. The bits changed by a move
+/// are tabu to be changed again for `tenure` iterations. The population in
+/// each generation consists of the current solution only.
+///
+/// # Reference
+///
+/// * Glover 1989, Tabu Search - Part I
+///
+#[derive(Debug, Clone)]
+pub struct TabuSearch {
+    neighborhood: Neighborhood,
+    aspiration: Aspiration,
+    tenure: usize,
+    sample_size: Option<usize>,
+
+    iteration: usize,
+    // the iteration until which a bit position is tabu
+    tabu_until: Vec<usize>,
+    best: Option<Individual<Binary>>,
+
+    // statistics of last generation
+    nneighbors: usize,
+    ntabu: usize,
+    naspirated: usize,
+}
+
+impl Default for TabuSearch {
+    fn default() -> Self {
+        Self {
+            neighborhood: Neighborhood::default(),
+            aspiration: Aspiration::default(),
+            tenure: 7,
+            sample_size: None,
+            iteration: 0,
+            tabu_until: vec![],
+            best: None,
+            nneighbors: 0,
+            ntabu: 0,
+            naspirated: 0,
+        }
+    }
+}
+
+impl TabuSearch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set neighborhood structure.
+    pub fn neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    /// Set aspiration criterion.
+    pub fn aspiration(mut self, aspiration: Aspiration) -> Self {
+        self.aspiration = aspiration;
+        self
+    }
+
+    /// Set tabu tenure, the number of iterations a changed bit is kept tabu.
+    /// The default is 7.
+    pub fn tenure(mut self, n: usize) -> Self {
+        self.tenure = n;
+        self
+    }
+
+    /// Evaluate a random sample of `n` neighbors instead of the full
+    /// neighborhood.
+    pub fn sample_size(mut self, n: usize) -> Self {
+        assert!(n > 0, "invalid sample size: {n}");
+        self.sample_size = Some(n);
+        self
+    }
+
+    /// Return the best individual found so far.
+    pub fn best(&self) -> Option<&Individual<Binary>> {
+        self.best.as_ref()
+    }
+}
+
+impl TabuSearch {
+    // Return moves as bit positions to flip.
+    fn moves<R: Rng + Sized>(&self, genome: &Binary, rng: &mut R) -> Vec<Vec<usize>> {
+        let n = genome.len();
+        let moves: Vec<_> = match self.neighborhood {
+            Neighborhood::BitFlip => (0..n).map(|i| vec![i]).collect(),
+            Neighborhood::Swap => (0..n)
+                .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+                .filter(|&(i, j)| genome[i] != genome[j])
+                .map(|(i, j)| vec![i, j])
+                .collect(),
+        };
+        match self.sample_size {
+            Some(k) if k < moves.len() => moves.choose_multiple(rng, k).cloned().collect(),
+            _ => moves,
+        }
+    }
+
+    fn is_tabu(&self, positions: &[usize]) -> bool {
+        positions.iter().any(|&i| self.tabu_until[i] > self.iteration)
+    }
+}
+
+impl<F, C> Evolve<Binary, F, C> for TabuSearch
+where
+    F: EvaluateFitness<Binary>,
+    C: EvaluateObjectiveValue<Binary>,
+{
+    fn next_generation(&mut self, cur_population: &Population<Binary>, valuer: &mut Valuer<Binary, F, C>) -> Population<Binary> {
+        let current = cur_population.best_member().expect("empty population").individual.to_owned();
+        let best = self.best.get_or_insert_with(|| current.clone()).clone();
+        if self.tabu_until.len() != current.genome().len() {
+            self.tabu_until = vec![0; current.genome().len()];
+        }
+        self.iteration += 1;
+
+        // 1. evaluate neighbors in parallel
+        let moves = {
+            let mut rng = get_rng!();
+            self.moves(current.genome(), &mut *rng)
+        };
+        let genomes: Vec<_> = moves
+            .iter()
+            .map(|m| {
+                let mut g = current.genome().clone();
+                g.flip(m.iter().copied());
+                g
+            })
+            .collect();
+        let created: HashMap<_, _> = valuer
+            .create_individuals(genomes.clone())
+            .into_iter()
+            .map(|x| (x.genome().to_owned(), x))
+            .collect();
+        let mut candidates = vec![best];
+        candidates.extend(genomes.iter().map(|g| created[g].clone()));
+        let fitness = valuer.evaluate_fitness(&candidates);
+        self.nneighbors = moves.len();
+
+        // 2. move to the best admissible neighbor
+        let mut order: Vec<_> = (0..moves.len()).collect();
+        order.sort_by(|&i, &j| float_ordering_maximize(&fitness[i + 1], &fitness[j + 1]));
+        self.ntabu = moves.iter().filter(|m| self.is_tabu(m)).count();
+        self.naspirated = 0;
+        let chosen = order.iter().copied().find(|&i| {
+            if !self.is_tabu(&moves[i]) {
+                true
+            } else if self.aspiration == Aspiration::BestSoFar && fitness[i + 1] > fitness[0] {
+                self.naspirated += 1;
+                true
+            } else {
+                false
+            }
+        });
+
+        let next = match chosen {
+            Some(i) => {
+                for &k in moves[i].iter() {
+                    self.tabu_until[k] = self.iteration + self.tenure;
+                }
+                if fitness[i + 1] > fitness[0] {
+                    self.best = Some(candidates[i + 1].clone());
+                }
+                candidates[i + 1].clone()
+            }
+            None => {
+                warn!("all moves are tabu, stay at current solution.");
+                current
+            }
+        };
+
+        valuer.build_population(vec![next]).with_size_limit(1)
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+        report.set("tabu/neighbors", self.nneighbors);
+        report.set("tabu/tabu_moves", self.ntabu);
+        report.set("tabu/aspirated", self.naspirated);
+        if let Some(best) = &self.best {
+            report.set("tabu/best_objective", best.objective_value());
+        }
+        report
+    }
+}
+// 2f8b6d4c ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[test]
+fn test_tabu_search() -> Result<()> {
+    use crate::engine::Engine;
+
+    let valuer = Valuer::new().with_fitness(Maximize).with_creator(OneMax);
+    let algo = TabuSearch::new().tenure(3);
+    let seeds = vec![Binary::from_str("000000000000")];
+    let mut engine = Engine::create().valuer(valuer).algorithm(algo).termination_nlast(100);
+    let mut best = 0.0;
+    for g in engine.evolve(&seeds).skip(1).take(15) {
+        let generation = g?;
+        assert_eq!(generation.report.get("tabu/neighbors").and_then(|x| x.as_count()), Some(12));
+        best = generation.report.get("tabu/best_objective").and_then(|x| x.as_number()).unwrap();
+    }
+    assert_eq!(best, 12.0);
+
+    // swapping keeps the number of ones
+    let valuer = Valuer::new().with_fitness(Maximize).with_creator(OneMax);
+    let algo = TabuSearch::new().neighborhood(Neighborhood::Swap).sample_size(5);
+    let seeds = vec![Binary::from_str("111000000000")];
+    let mut engine = Engine::create().valuer(valuer).algorithm(algo).termination_nlast(100);
+    for g in engine.evolve(&seeds).skip(1).take(5) {
+        let generation = g?;
+        let m = generation.population.best_member().unwrap();
+        assert_eq!(m.genome().iter().filter(|&&b| b).count(), 3);
+        assert_eq!(generation.report.get("tabu/neighbors").and_then(|x| x.as_count()), Some(5));
+    }
+
+    Ok(())
+}
+// test:1 ends here
@@ -5,6 +5,7 @@ use crate::common::*;
 use crate::fitness::*;
 use crate::individual::*;
 use crate::population::*;
+use crate::report::Report;
 // imports:1 ends here
 
 // base
@@ -78,7 +79,7 @@ impl Terminate for RunningMean {
             .individual
             .objective_value();
 
-        if generation.index < self.nlast {
+        if self.scores.len() < self.nlast {
             self.scores.push(best);
             return false;
         } else {
@@ -101,6 +102,36 @@ impl Terminate for RunningMean {
 }
 // running mean:1 ends here
 
+// [[file:../spdkit.note::d2c7a4e9][d2c7a4e9]]
+/// Terminates simulation if a diversity measure reported in generation, e.g.
+/// "diversity/mean_distance", drops below `threshold`, i.e. the population has
+/// collapsed.
+#[derive(Debug, Clone)]
+pub struct DiversityCollapse {
+    key: String,
+    threshold: f64,
+}
+
+impl DiversityCollapse {
+    /// Construct with the report `key` of diversity measure and `threshold`.
+    pub fn new(key: &str, threshold: f64) -> Self {
+        Self {
+            key: key.into(),
+            threshold,
+        }
+    }
+}
+
+impl Terminate for DiversityCollapse {
+    fn meets<G: Genome>(&mut self, generation: &Generation<G>) -> bool {
+        match generation.report.get(&self.key).and_then(|x| x.as_number()) {
+            Some(x) => x < self.threshold,
+            None => false,
+        }
+    }
+}
+// d2c7a4e9 ends here
+
 // generation
 
 // [[file:~/Workspace/Programming/structure-predication/spdkit/spdkit.note::*generation][generation:1]]
@@ -112,6 +143,11 @@ where
 {
     pub index: usize,
     pub population: Population<G>,
+    /// Statistics reported by the evolution algorithm for this generation.
+    pub report: Report,
+    /// The best unique individuals ever seen until this generation, if hall
+    /// of fame is enabled in `Engine`.
+    pub hall_of_fame: Vec<Individual<G>>,
 }
 
 impl<G> Generation<G>
@@ -133,6 +169,11 @@ where
         for m in members {
             println!(" {:}", m);
         }
+
+        if !self.report.is_empty() {
+            println!("report:");
+            print!("{}", self.report);
+        }
     }
 
     /// Return the best individual in this generation.
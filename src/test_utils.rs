@@ -0,0 +1,44 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use crate::common::*;
+use crate::encoding::RealVector;
+use crate::engine::{Engine, Evolve};
+use crate::fitness::Minimize;
+use crate::gears::Valuer;
+use crate::individual::*;
+use crate::termination::Generation;
+// imports:1 ends here
+
+// [[file:../spdkit.note::3d8b61f5][3d8b61f5]]
+/// The sphere function, minimized to zero at the origin.
+#[derive(Debug, Clone)]
+pub(crate) struct Sphere;
+
+impl EvaluateObjectiveValue<RealVector> for Sphere {
+    fn evaluate(&self, genome: &RealVector) -> f64 {
+        genome.iter().map(|x| x * x).sum()
+    }
+}
+
+/// Minimize `Sphere` using `algo` from `seeds` for `n` generations, calling
+/// `check` on each generation. Return the best objective value ever seen.
+pub(crate) fn minimize_sphere<E>(
+    algo: E,
+    seeds: &[RealVector],
+    n: usize,
+    mut check: impl FnMut(&Generation<RealVector>),
+) -> Result<f64>
+where
+    E: Evolve<RealVector, Minimize, Sphere>,
+{
+    let valuer = Valuer::new().with_fitness(Minimize).with_creator(Sphere);
+    // never terminated for stagnation
+    let mut engine = Engine::create().valuer(valuer).algorithm(algo).termination_nlast(n + 2);
+    let mut best = f64::MAX;
+    for g in engine.evolve(seeds).take(n) {
+        let generation = g?;
+        check(&generation);
+        best = best.min(generation.population.best_member().unwrap().objective_value());
+    }
+    Ok(best)
+}
+// 3d8b61f5 ends here
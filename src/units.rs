@@ -0,0 +1,188 @@
+// [[file:../spdkit.note::*imports][imports:1]]
+use crate::common::*;
+// imports:1 ends here
+
+// [[file:../spdkit.note::5f2c8e1a][5f2c8e1a]]
+/// The molar gas constant in kJ/(mol·K).
+const GAS_CONSTANT: f64 = 0.0083144626;
+
+/// Supported units for energy (objective value) of individuals.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EnergyUnit {
+    /// Electron volt
+    #[default]
+    #[serde(rename = "eV")]
+    ElectronVolt,
+    /// Hartree, the atomic unit of energy
+    #[serde(rename = "Hartree", alias = "au")]
+    Hartree,
+    /// kcal/mol
+    #[serde(rename = "kcal/mol", alias = "kcal")]
+    KcalPerMol,
+    /// kJ/mol
+    #[serde(rename = "kJ/mol", alias = "kJ")]
+    KjPerMol,
+}
+
+impl EnergyUnit {
+    /// Return the value of one `self` unit in kJ/mol.
+    pub fn in_kj_per_mol(self) -> f64 {
+        match self {
+            EnergyUnit::ElectronVolt => 96.485332,
+            EnergyUnit::Hartree => 2625.4996,
+            EnergyUnit::KcalPerMol => 4.184,
+            EnergyUnit::KjPerMol => 1.0,
+        }
+    }
+
+    /// Convert `value` in `self` unit into `unit`.
+    pub fn convert(self, value: f64, unit: EnergyUnit) -> f64 {
+        value * self.in_kj_per_mol() / unit.in_kj_per_mol()
+    }
+}
+
+impl std::fmt::Display for EnergyUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match self {
+            EnergyUnit::ElectronVolt => "eV",
+            EnergyUnit::Hartree => "Hartree",
+            EnergyUnit::KcalPerMol => "kcal/mol",
+            EnergyUnit::KjPerMol => "kJ/mol",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for EnergyUnit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let u = match s {
+            "eV" => EnergyUnit::ElectronVolt,
+            "au" | "Hartree" => EnergyUnit::Hartree,
+            "kcal" | "kcal/mol" => EnergyUnit::KcalPerMol,
+            "kJ" | "kJ/mol" => EnergyUnit::KjPerMol,
+            _ => bail!("unknown energy unit: {s:?}"),
+        };
+        Ok(u)
+    }
+}
+// 5f2c8e1a ends here
+
+// [[file:../spdkit.note::0b7d93e4][0b7d93e4]]
+/// Thermodynamic temperature in Kelvin.
+///
+/// In configuration files temperature can be given either in Kelvin or as
+/// the kT energy scale, e.g. `{ kelvin = 300.0 }` or `{ kt = 0.1, unit = "eV" }`.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "TemperatureRepr", into = "TemperatureRepr")]
+pub struct Temperature(f64);
+
+impl Temperature {
+    /// Construct from temperature in Kelvin.
+    ///
+    /// # Panics
+    ///
+    /// * panics if `kelvin` is not a finite positive number.
+    pub fn kelvin(kelvin: f64) -> Self {
+        assert!(kelvin.is_finite() && kelvin > 0.0, "invalid temperature: {kelvin} K");
+        Self(kelvin)
+    }
+
+    /// Construct from the kT energy scale `kt` in `unit`.
+    pub fn from_kt(kt: f64, unit: EnergyUnit) -> Self {
+        Self::kelvin(kt * unit.in_kj_per_mol() / GAS_CONSTANT)
+    }
+
+    /// Return temperature in Kelvin.
+    pub fn to_kelvin(self) -> f64 {
+        self.0
+    }
+
+    /// Return the kT energy scale in `unit`.
+    pub fn kt(self, unit: EnergyUnit) -> f64 {
+        self.0 * GAS_CONSTANT / unit.in_kj_per_mol()
+    }
+
+    /// Return the Boltzmann factor of energy change `delta` in `unit`.
+    pub fn boltzmann_factor(self, delta: f64, unit: EnergyUnit) -> f64 {
+        (-delta / self.kt(unit)).exp()
+    }
+}
+
+/// Temperature in Kelvin.
+impl From<f64> for Temperature {
+    fn from(kelvin: f64) -> Self {
+        Self::kelvin(kelvin)
+    }
+}
+
+impl std::fmt::Display for Temperature {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} K", self.0)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum TemperatureRepr {
+    Kelvin {
+        kelvin: f64,
+    },
+    Kt {
+        kt: f64,
+        #[serde(default)]
+        unit: EnergyUnit,
+    },
+}
+
+impl TryFrom<TemperatureRepr> for Temperature {
+    type Error = Error;
+
+    fn try_from(t: TemperatureRepr) -> Result<Self> {
+        let kelvin = match t {
+            TemperatureRepr::Kelvin { kelvin } => kelvin,
+            TemperatureRepr::Kt { kt, unit } => kt * unit.in_kj_per_mol() / GAS_CONSTANT,
+        };
+        ensure!(kelvin.is_finite() && kelvin > 0.0, "invalid temperature: {kelvin} K");
+        Ok(Self(kelvin))
+    }
+}
+
+impl From<Temperature> for TemperatureRepr {
+    fn from(t: Temperature) -> Self {
+        TemperatureRepr::Kelvin { kelvin: t.0 }
+    }
+}
+// 0b7d93e4 ends here
+
+// [[file:../spdkit.note::*test][test:1]]
+#[test]
+fn test_units() -> Result<()> {
+    use vecfx::approx::assert_relative_eq;
+
+    let u: EnergyUnit = "au".parse()?;
+    assert_eq!(u, EnergyUnit::Hartree);
+    assert!("kcal/K".parse::<EnergyUnit>().is_err());
+    assert_relative_eq!(EnergyUnit::Hartree.convert(1.0, EnergyUnit::ElectronVolt), 27.2114, epsilon = 1e-3);
+
+    let t = Temperature::kelvin(300.0);
+    assert_relative_eq!(t.kt(EnergyUnit::KjPerMol), 2.494, epsilon = 1e-3);
+    assert_relative_eq!(t.kt(EnergyUnit::ElectronVolt), 0.025852, epsilon = 1e-5);
+    let t2 = Temperature::from_kt(t.kt(EnergyUnit::KcalPerMol), EnergyUnit::KcalPerMol);
+    assert_relative_eq!(t.to_kelvin(), t2.to_kelvin(), epsilon = 1e-8);
+
+    #[derive(Default, Serialize, Deserialize)]
+    struct Settings {
+        temperature: Option<Temperature>,
+    }
+    let s = Settings::from_json(r#"{"temperature": {"kt": 0.025852, "unit": "eV"}}"#)?;
+    assert_relative_eq!(s.temperature.unwrap().to_kelvin(), 300.0, epsilon = 1e-2);
+    // invalid input is an error instead of a panic
+    assert!(Settings::from_json(r#"{"temperature": {"kelvin": -1.0}}"#).is_err());
+    assert!(Settings::from_json(r#"{"temperature": {"kelvin": 0.0}}"#).is_err());
+    assert!(Settings::from_json(r#"{"temperature": {"kt": 0.0}}"#).is_err());
+
+    Ok(())
+}
+// test:1 ends here